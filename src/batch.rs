@@ -0,0 +1,65 @@
+use crate::*;
+
+/// How many values the distributions draw at a time.
+pub(crate) const BATCH: usize = 256;
+
+/// How many blocks `WordBatch` draws at a time. Whole blocks keep its words in step with
+/// `fill_core`, which would discard the rest of a partly used block.
+const BLOCKS_PER_BATCH: usize = 16;
+
+/// Hands out u64s of output, drawn from a prng a batch at a time,
+/// for algorithms that need an unpredictable number of values per sample.
+pub(crate) struct WordBatch<
+    'a,
+    const LANES: usize,
+    const SHL_FIRST: bool,
+    const SHL: u32,
+    const SHR: u32,
+> {
+    rng: &'a mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+    blocks: [[u64; LANES]; BLOCKS_PER_BATCH],
+    position: usize,
+}
+impl<'a, const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    WordBatch<'a, LANES, SHL_FIRST, SHL, SHR>
+{
+    pub(crate) fn new(rng: &'a mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>) -> Self {
+        Self {
+            rng,
+            blocks: [[0; LANES]; BLOCKS_PER_BATCH],
+            position: LANES * BLOCKS_PER_BATCH,
+        }
+    }
+    #[inline(always)]
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let words = self.blocks.as_flattened_mut();
+        if self.position == words.len() {
+            self.rng.fill_core(words);
+            self.position = 0;
+        }
+        let word = words[self.position];
+        self.position += 1;
+        word
+    }
+    /// A uniform value in `[0, 1)`, the same as [`XorshiftrWideWith::fill_f64`] would make
+    /// from the next u64 of output.
+    #[inline(always)]
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * fill::F64_SCALE
+    }
+    /// An unbiased uniform value in `[0, span)`, using Lemire's multiply-and-reject method.
+    /// `span` must not be zero.
+    #[inline(always)]
+    pub(crate) fn below(&mut self, span: u64) -> u64 {
+        let mut product = self.next_u64() as u128 * span as u128;
+        // Only a low word below `span` can be below the rejection threshold,
+        // so the division is rarely needed
+        if (product as u64) < span {
+            let threshold = span.wrapping_neg() % span;
+            while (product as u64) < threshold {
+                product = self.next_u64() as u128 * span as u128;
+            }
+        }
+        (product >> 64) as u64
+    }
+}
@@ -0,0 +1,108 @@
+use crate::*;
+
+/// A wrapper around [`XorshiftrWide`] that keeps the unused part of its last blocks.
+///
+/// A plain [`XorshiftrWide`] generates a whole block of `LANES` words for a short tail
+/// and throws away what doesn't fit, so filling 10 then 6 words gives different output
+/// to filling 16 at once. This wrapper hands the leftover words out on the next call,
+/// so the output is the same however the caller splits its buffers. The same buffering
+/// serves single values through [`Self::next_u64`], [`Self::next_u32`] and [`Iterator`].
+#[derive(Clone, Debug)]
+pub struct BufferedXorshiftrWide<
+    const LANES: usize = 16,
+    const SHL_FIRST: bool = DEFAULT_SHL_FIRST,
+    const SHL: u32 = DEFAULT_SHL,
+    const SHR: u32 = DEFAULT_SHR,
+> {
+    rng: XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+    blocks: [[u64; LANES]; BLOCKS_PER_REFILL],
+    // Index of the next unused word in `blocks`, equal to `BUFFER_LEN` when they're used up
+    position: usize,
+}
+
+/// How many blocks are generated at a time for leftovers and single values.
+const BLOCKS_PER_REFILL: usize = 8;
+
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    BufferedXorshiftrWide<LANES, SHL_FIRST, SHL, SHR>
+{
+    const BUFFER_LEN: usize = LANES * BLOCKS_PER_REFILL;
+    /// Wraps an existing prng instance, starting with no leftover output.
+    pub fn new(rng: XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>) -> Self {
+        Self {
+            rng,
+            blocks: [[0; LANES]; BLOCKS_PER_REFILL],
+            position: Self::BUFFER_LEN,
+        }
+    }
+    /// Unwraps the inner prng instance, discarding any leftover output.
+    pub fn into_inner(self) -> XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR> {
+        self.rng
+    }
+    /// Returns how many generated words are waiting to be handed out.
+    pub fn leftover_len(&self) -> usize {
+        Self::BUFFER_LEN - self.position
+    }
+    /// Fills a slice of u64 with random data, continuing where the previous call left off.
+    pub fn fill_u64_buffer(&mut self, buffer: &mut [u64]) {
+        // Hand out leftovers from the previous call first
+        let qty_from_leftovers = buffer.len().min(self.leftover_len());
+        let (from_leftovers, rest) = buffer.split_at_mut(qty_from_leftovers);
+        from_leftovers
+            .copy_from_slice(&self.blocks.as_flattened()[self.position..][..qty_from_leftovers]);
+        self.position += qty_from_leftovers;
+        // Whole blocks go straight into the destination
+        let whole_blocks_len = rest.len() / LANES * LANES;
+        let (whole_blocks, tail) = rest.split_at_mut(whole_blocks_len);
+        self.rng.fill_core(whole_blocks);
+        // A short tail takes the start of fresh blocks, and the remainder is kept for later
+        if !tail.is_empty() {
+            self.refill();
+            tail.copy_from_slice(&self.blocks.as_flattened()[..tail.len()]);
+            self.position = tail.len();
+        }
+    }
+    /// Replaces the buffered blocks with fresh ones, and starts handing them out from the beginning.
+    fn refill(&mut self) {
+        self.rng.fill_core(self.blocks.as_flattened_mut());
+        self.position = 0;
+    }
+    /// Returns the next u64 of output, generating fresh blocks when the current ones are used up.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        if self.position == Self::BUFFER_LEN {
+            cold();
+            self.refill();
+        }
+        let word = self.blocks.as_flattened()[self.position];
+        self.position += 1;
+        word
+    }
+    /// Returns the low half of the next u64 of output, discarding the high half,
+    /// so each call uses up a whole u64. [`XorshiftrWideWith::fill_u32`] uses both halves.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+}
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32> Iterator
+    for BufferedXorshiftrWide<LANES, SHL_FIRST, SHL, SHR>
+{
+    type Item = u64;
+    /// Returns the next u64 of output, like [`Self::next_u64`]. This never returns `None`.
+    #[inline]
+    fn next(&mut self) -> Option<u64> {
+        Some(self.next_u64())
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    From<XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>>
+    for BufferedXorshiftrWide<LANES, SHL_FIRST, SHL, SHR>
+{
+    fn from(rng: XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>) -> Self {
+        Self::new(rng)
+    }
+}
@@ -0,0 +1,121 @@
+use crate::*;
+
+const MAGIC: [u8; 8] = *b"XSHRWIDE";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 20;
+const CHECKSUM_LEN: usize = 4;
+
+/// A checkpoint is laid out as follows, with every integer little-endian:
+///
+/// | offset | size          | contents                                       |
+/// |--------|---------------|------------------------------------------------|
+/// | 0      | 8             | magic number, `b"XSHRWIDE"`                    |
+/// | 8      | 4             | format version, currently 1                    |
+/// | 12     | 4             | `LANES`                                        |
+/// | 16     | 1             | `SHL_FIRST`, as 0 or 1                         |
+/// | 17     | 1             | `SHL`                                          |
+/// | 18     | 1             | `SHR`                                          |
+/// | 19     | 1             | reserved, 0                                    |
+/// | 20     | `8 * LANES`   | first state word of each lane, in lane order   |
+/// | ...    | `8 * LANES`   | second state word of each lane, in lane order  |
+/// | ...    | 4             | CRC-32 (IEEE) of every preceding byte          |
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>
+{
+    /// Serializes the prng into a self-describing checkpoint.
+    ///
+    /// Besides the state, the checkpoint records the lane count and shift configuration,
+    /// so [`Self::from_checkpoint`] can refuse to resume it with a different algorithm.
+    pub fn to_checkpoint(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(checkpoint_len(LANES));
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(LANES as u32).to_le_bytes());
+        bytes.extend_from_slice(&[SHL_FIRST as u8, SHL as u8, SHR as u8, 0]);
+        for word in self.state.as_flattened() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        let checksum = crc32(&bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+    /// Restores a prng from a checkpoint written by [`Self::to_checkpoint`].
+    ///
+    /// Fails if the checkpoint is corrupt, or was written by a prng with a different lane
+    /// count or shift configuration.
+    pub fn from_checkpoint(checkpoint: &[u8]) -> Result<Self, CheckpointError> {
+        if checkpoint.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err(CheckpointError::WrongLength);
+        }
+        if checkpoint[..8] != MAGIC {
+            return Err(CheckpointError::BadMagic);
+        }
+        let (contents, checksum) = checkpoint.split_at(checkpoint.len() - CHECKSUM_LEN);
+        if crc32(contents).to_le_bytes() != checksum {
+            return Err(CheckpointError::ChecksumMismatch);
+        }
+        let version = u32::from_le_bytes(contents[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(CheckpointError::UnsupportedVersion(version));
+        }
+        let lanes = u32::from_le_bytes(contents[12..16].try_into().unwrap());
+        if lanes as usize != LANES {
+            return Err(CheckpointError::LaneMismatch {
+                expected: LANES,
+                found: lanes,
+            });
+        }
+        let shifts = ShiftConfig {
+            shl_first: contents[16] != 0,
+            shl: contents[17] as u32,
+            shr: contents[18] as u32,
+        };
+        if contents[16] > 1 || shifts != Self::SHIFTS {
+            return Err(CheckpointError::ShiftMismatch {
+                expected: Self::SHIFTS,
+                found: shifts,
+            });
+        }
+        if checkpoint.len() != checkpoint_len(LANES) {
+            return Err(CheckpointError::WrongLength);
+        }
+        let mut state = [[0; LANES]; 2];
+        let words = contents[HEADER_LEN..].chunks_exact(8);
+        for (word, bytes) in state.as_flattened_mut().iter_mut().zip(words) {
+            *word = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        Ok(Self::try_from_state(state)?)
+    }
+}
+
+fn checkpoint_len(lanes: usize) -> usize {
+    HEADER_LEN + 16 * lanes + CHECKSUM_LEN
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc = CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
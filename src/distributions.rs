@@ -0,0 +1,122 @@
+//! Non-uniform distributions, sampled in bulk into buffers.
+//!
+//! Each distribution is validated once when it's created, then fills whole buffers from a
+//! [`XorshiftrWideWith`], drawing the uniform and normal values it needs in batches to keep
+//! the wide prng's throughput.
+
+use crate::XorshiftrWideWith;
+use crate::batch::{BATCH, WordBatch};
+use core::fmt;
+
+pub use alias::AliasTable;
+pub use continuous::{Beta, ChiSquared, Exponential, Gamma};
+pub use discrete::{Bernoulli, Binomial, Geometric, Poisson};
+mod alias;
+mod continuous;
+mod discrete;
+
+/// The reason a distribution's parameters were rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParameterError {
+    /// The named parameter was NaN or infinite.
+    NotFinite(&'static str),
+    /// The named parameter had to be greater than zero, but wasn't.
+    NotPositive(&'static str),
+    /// The named parameter was a probability outside `[0, 1]`.
+    NotProbability(&'static str),
+}
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFinite(name) => write!(f, "{name} must be finite"),
+            Self::NotPositive(name) => write!(f, "{name} must be greater than zero"),
+            Self::NotProbability(name) => write!(f, "{name} must be between 0 and 1"),
+        }
+    }
+}
+impl std::error::Error for ParameterError {}
+
+/// The reason a set of categorical weights was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WeightError {
+    /// There were no weights.
+    Empty,
+    /// There were more than 2^32 weights, too many to index with a u32.
+    TooManyCategories,
+    /// The weight at `index` was NaN or infinite.
+    NotFinite { index: usize },
+    /// The weight at `index` was negative.
+    Negative { index: usize },
+    /// Every weight was zero.
+    AllZero,
+}
+impl fmt::Display for WeightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no weights were given"),
+            Self::TooManyCategories => write!(f, "there are more than 2^32 weights"),
+            Self::NotFinite { index } => write!(f, "weight {index} is not finite"),
+            Self::Negative { index } => write!(f, "weight {index} is negative"),
+            Self::AllZero => write!(f, "every weight is zero"),
+        }
+    }
+}
+impl std::error::Error for WeightError {}
+
+/// Checks that a parameter is finite and greater than zero.
+fn check_positive(value: f64, name: &'static str) -> Result<f64, ParameterError> {
+    if !value.is_finite() {
+        Err(ParameterError::NotFinite(name))
+    } else if value <= 0.0 {
+        Err(ParameterError::NotPositive(name))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Checks that a parameter is a probability in `[0, 1]`.
+fn check_probability(value: f64, name: &'static str) -> Result<f64, ParameterError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(ParameterError::NotProbability(name))
+    }
+}
+
+/// The natural logarithm of the gamma function, from Stirling's series,
+/// shifting small arguments up to 7 first.
+fn ln_gamma(x: f64) -> f64 {
+    // B(2k) / (2k (2k - 1)), for the Bernoulli numbers B
+    const COEFFICIENTS: [f64; 10] = [
+        1.0 / 12.0,
+        -1.0 / 360.0,
+        1.0 / 1260.0,
+        -1.0 / 1680.0,
+        1.0 / 1188.0,
+        -691.0 / 360_360.0,
+        1.0 / 156.0,
+        -3617.0 / 122_400.0,
+        43_867.0 / 244_188.0,
+        -174_611.0 / 125_400.0,
+    ];
+    const LN_2PI: f64 = 1.837_877_066_409_345_5;
+    if x == 1.0 || x == 2.0 {
+        return 0.0;
+    }
+    let shift = if x < 7.0 { (7.0 - x).floor() } else { 0.0 };
+    let shifted = x + shift;
+    let inverse_squared = 1.0 / (shifted * shifted);
+    let series = COEFFICIENTS
+        .iter()
+        .rev()
+        .fold(0.0, |sum, &coefficient| sum * inverse_squared + coefficient);
+    let mut result = series / shifted + 0.5 * LN_2PI + (shifted - 0.5) * shifted.ln() - shifted;
+    let mut below = shifted;
+    for _ in 0..shift as u32 {
+        below -= 1.0;
+        result -= below.ln();
+    }
+    result
+}
@@ -0,0 +1,102 @@
+use super::*;
+
+/// A categorical distribution over `0..n`, sampled with Walker's alias method
+/// as set up by Vose's algorithm.
+///
+/// Building the table takes time proportional to the number of categories, after which
+/// every sample takes one u64 of output however many categories there are.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AliasTable {
+    // For each column, a u64 below `thresholds[column]` picks the column itself,
+    // and anything else picks `aliases[column]`
+    thresholds: Vec<u64>,
+    aliases: Vec<u32>,
+}
+impl AliasTable {
+    /// Builds a table that samples each index with probability proportional to its weight.
+    ///
+    /// Weights must be finite and non-negative, at least one must be greater than zero,
+    /// and there can be at most 2^32 of them.
+    pub fn new(weights: &[f64]) -> Result<Self, WeightError> {
+        if weights.is_empty() {
+            return Err(WeightError::Empty);
+        }
+        if weights.len() - 1 > u32::MAX as usize {
+            return Err(WeightError::TooManyCategories);
+        }
+        let mut largest = 0.0f64;
+        for (index, &weight) in weights.iter().enumerate() {
+            if !weight.is_finite() {
+                return Err(WeightError::NotFinite { index });
+            } else if weight < 0.0 {
+                return Err(WeightError::Negative { index });
+            }
+            largest = largest.max(weight);
+        }
+        if largest == 0.0 {
+            return Err(WeightError::AllZero);
+        }
+        // Dividing by the largest weight first keeps the total from overflowing
+        let total: f64 = weights.iter().map(|&weight| weight / largest).sum();
+        let n = weights.len();
+        let scale = n as f64 / total / largest;
+        let mut probabilities: Vec<f64> = weights.iter().map(|&weight| weight * scale).collect();
+        let mut aliases: Vec<u32> = (0..n as u64).map(|index| index as u32).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&index| probabilities[index] < 1.0);
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            // Top up the column of an underfull category with an overfull one
+            small.pop();
+            aliases[less] = more as u32;
+            probabilities[more] -= 1.0 - probabilities[less];
+            if probabilities[more] < 1.0 {
+                large.pop();
+                small.push(more);
+            }
+        }
+        // Whatever is left over is only short of 1 by rounding, so takes its whole column
+        for index in small.into_iter().chain(large) {
+            probabilities[index] = 1.0;
+            aliases[index] = index as u32;
+        }
+        let thresholds = probabilities
+            .iter()
+            .map(|&probability| (probability * 2f64.powi(64)) as u64)
+            .collect();
+        Ok(Self {
+            thresholds,
+            aliases,
+        })
+    }
+    /// The number of categories.
+    pub fn len(&self) -> usize {
+        self.thresholds.len()
+    }
+    /// Always false, as a table needs at least one category.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+    /// Fills a slice of u32 with category indices.
+    ///
+    /// Each u64 of output is multiplied by the number of categories, choosing a column from the
+    /// high half of the product and between the column and its alias from the low half.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [u32],
+    ) {
+        let n = self.len() as u128;
+        let (thresholds, aliases) = (&self.thresholds[..], &self.aliases[..]);
+        rng.fill_mapped(destination_buffer, |word| {
+            let product = word as u128 * n;
+            let column = (product >> 64) as usize;
+            // The column's full share of probability saturates at `u64::MAX`, but its
+            // alias is itself, so the choice doesn't matter
+            if (product as u64) < thresholds[column] {
+                [column as u32]
+            } else {
+                [aliases[column]]
+            }
+        });
+    }
+}
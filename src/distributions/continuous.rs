@@ -0,0 +1,200 @@
+use super::*;
+use crate::XorshiftrWideWith;
+
+/// The exponential distribution with rate `lambda`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exponential {
+    lambda: f64,
+}
+impl Exponential {
+    /// Creates the distribution, which requires `lambda` to be finite and positive.
+    pub fn new(lambda: f64) -> Result<Self, ParameterError> {
+        Ok(Self {
+            lambda: check_positive(lambda, "lambda")?,
+        })
+    }
+    /// Fills a slice of f64 with samples, by inversion of uniform values in `(0, 1]`.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [f64],
+    ) {
+        rng.fill_f64_open_closed(destination_buffer);
+        let scale = -1.0 / self.lambda;
+        for value in destination_buffer {
+            *value = value.ln() * scale;
+        }
+    }
+}
+
+/// The gamma distribution with the given `shape` and `scale`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gamma {
+    shape: f64,
+    scale: f64,
+}
+impl Gamma {
+    /// Creates the distribution, which requires `shape` and `scale` to be finite and positive.
+    pub fn new(shape: f64, scale: f64) -> Result<Self, ParameterError> {
+        Ok(Self {
+            shape: check_positive(shape, "shape")?,
+            scale: check_positive(scale, "scale")?,
+        })
+    }
+    /// Fills a slice of f64 with samples, using Marsaglia and Tsang's method.
+    ///
+    /// Normal and uniform candidates are drawn in batches, and the few that are rejected are
+    /// made up for in the next batch. Shapes below 1 sample with the shape increased by 1,
+    /// then multiply by `u^(1 / shape)` for another uniform `u`.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [f64],
+    ) {
+        self.fill_at_least_one(rng, destination_buffer);
+        if self.shape < 1.0 {
+            let exponent = 1.0 / self.shape;
+            self.for_each_boost(rng, destination_buffer, |value, u| {
+                *value *= u.powf(exponent);
+            });
+        }
+    }
+    /// Fills a slice of f64 with the natural logarithms of samples.
+    ///
+    /// Small shapes make samples so close to zero that they can underflow,
+    /// which this avoids by applying the boost for shapes below 1 in log space.
+    fn fill_ln<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [f64],
+    ) {
+        self.fill_at_least_one(rng, destination_buffer);
+        for value in destination_buffer.iter_mut() {
+            *value = value.ln();
+        }
+        if self.shape < 1.0 {
+            let exponent = 1.0 / self.shape;
+            self.for_each_boost(rng, destination_buffer, |value, u| {
+                *value += u.ln() * exponent;
+            });
+        }
+    }
+    /// Fills with samples for the shape, or the shape plus 1 when it's below 1.
+    fn fill_at_least_one<
+        const LANES: usize,
+        const SHL_FIRST: bool,
+        const SHL: u32,
+        const SHR: u32,
+    >(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [f64],
+    ) {
+        let shape = if self.shape < 1.0 {
+            self.shape + 1.0
+        } else {
+            self.shape
+        };
+        let d = shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        let mut normals = [0.0; BATCH];
+        let mut uniforms = [0.0; BATCH];
+        let mut filled = 0;
+        while filled < destination_buffer.len() {
+            let candidates = (destination_buffer.len() - filled).min(BATCH);
+            rng.fill_normal_f64(&mut normals[..candidates], 0.0, 1.0);
+            rng.fill_f64_open_closed(&mut uniforms[..candidates]);
+            for (&z, &u) in normals[..candidates].iter().zip(&uniforms[..candidates]) {
+                let v = 1.0 + c * z;
+                if v <= 0.0 {
+                    continue;
+                }
+                let v = v * v * v;
+                let z_squared = z * z;
+                // The squeeze accepts most candidates without any logarithms
+                let accepted = u < 1.0 - 0.0331 * z_squared * z_squared
+                    || u.ln() < 0.5 * z_squared + d * (1.0 - v + v.ln());
+                if accepted {
+                    destination_buffer[filled] = d * v * self.scale;
+                    filled += 1;
+                }
+            }
+        }
+    }
+    /// Calls `boost` on every value along with a uniform value in `(0, 1]`.
+    fn for_each_boost<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [f64],
+        boost: impl Fn(&mut f64, f64),
+    ) {
+        let mut uniforms = [0.0; BATCH];
+        for chunk in destination_buffer.chunks_mut(BATCH) {
+            let uniforms = &mut uniforms[..chunk.len()];
+            rng.fill_f64_open_closed(uniforms);
+            for (value, &u) in chunk.iter_mut().zip(uniforms.iter()) {
+                boost(value, u);
+            }
+        }
+    }
+}
+
+/// The beta distribution with shape parameters `alpha` and `beta`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Beta {
+    alpha: Gamma,
+    beta: Gamma,
+}
+impl Beta {
+    /// Creates the distribution, which requires `alpha` and `beta` to be finite and positive.
+    pub fn new(alpha: f64, beta: f64) -> Result<Self, ParameterError> {
+        Ok(Self {
+            alpha: Gamma::new(check_positive(alpha, "alpha")?, 1.0)?,
+            beta: Gamma::new(check_positive(beta, "beta")?, 1.0)?,
+        })
+    }
+    /// Fills a slice of f64 with samples, as `x / (x + y)` for gamma samples `x` and `y`
+    /// with shapes `alpha` and `beta`.
+    ///
+    /// This is computed from the samples' logarithms, as `1 / (1 + exp(ln y - ln x))`,
+    /// so small shapes can't make both samples underflow to zero.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [f64],
+    ) {
+        self.alpha.fill_ln(rng, destination_buffer);
+        let mut others = [0.0; BATCH];
+        for chunk in destination_buffer.chunks_mut(BATCH) {
+            let others = &mut others[..chunk.len()];
+            self.beta.fill_ln(rng, others);
+            for (value, &other) in chunk.iter_mut().zip(others.iter()) {
+                *value = 1.0 / (1.0 + (other - *value).exp());
+            }
+        }
+    }
+}
+
+/// The chi-squared distribution with `degrees_of_freedom` degrees of freedom.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChiSquared {
+    gamma: Gamma,
+}
+impl ChiSquared {
+    /// Creates the distribution, which requires `degrees_of_freedom` to be finite and positive.
+    pub fn new(degrees_of_freedom: f64) -> Result<Self, ParameterError> {
+        let degrees_of_freedom = check_positive(degrees_of_freedom, "degrees_of_freedom")?;
+        Ok(Self {
+            gamma: Gamma::new(degrees_of_freedom / 2.0, 2.0)?,
+        })
+    }
+    /// Fills a slice of f64 with samples, from the equivalent gamma distribution
+    /// with shape `degrees_of_freedom / 2` and scale 2.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [f64],
+    ) {
+        self.gamma.fill(rng, destination_buffer);
+    }
+}
@@ -0,0 +1,415 @@
+use super::*;
+
+/// The Bernoulli distribution, which is `true` with probability `p`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bernoulli {
+    // A u64 of output below this is `true`, unless `always` is set for `p == 1`
+    threshold: u64,
+    always: bool,
+}
+impl Bernoulli {
+    /// Creates the distribution, which requires `p` to be in `[0, 1]`.
+    pub fn new(p: f64) -> Result<Self, ParameterError> {
+        let p = check_probability(p, "p")?;
+        Ok(Self {
+            threshold: (p * 2f64.powi(64)) as u64,
+            always: p == 1.0,
+        })
+    }
+    /// Fills a slice of bool with samples, comparing each u64 of output against `p * 2^64`.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [bool],
+    ) {
+        let (threshold, always) = (self.threshold, self.always);
+        rng.fill_mapped(destination_buffer, |word| [always | (word < threshold)]);
+    }
+}
+
+/// The geometric distribution, counting the failures before the first success of
+/// independent trials that each succeed with probability `p`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geometric {
+    // 1 / ln(1 - p), which is -0.0 when `p == 1`
+    inverse_ln_failure: f64,
+}
+impl Geometric {
+    /// Creates the distribution, which requires `p` to be in `(0, 1]`.
+    pub fn new(p: f64) -> Result<Self, ParameterError> {
+        let p = check_probability(p, "p")?;
+        if p == 0.0 {
+            return Err(ParameterError::NotPositive("p"));
+        }
+        Ok(Self {
+            inverse_ln_failure: 1.0 / (-p).ln_1p(),
+        })
+    }
+    /// Fills a slice of u64 with samples, by inversion of uniform values in `(0, 1]`.
+    /// Samples too large for a u64 saturate at `u64::MAX`.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [u64],
+    ) {
+        let mut uniforms = [0.0; BATCH];
+        for chunk in destination_buffer.chunks_mut(BATCH) {
+            let uniforms = &mut uniforms[..chunk.len()];
+            rng.fill_f64_open_closed(uniforms);
+            for (value, &u) in chunk.iter_mut().zip(uniforms.iter()) {
+                *value = (u.ln() * self.inverse_ln_failure).floor() as u64;
+            }
+        }
+    }
+}
+
+/// The Poisson distribution with mean `lambda`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Poisson {
+    lambda: f64,
+    method: PoissonMethod,
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PoissonMethod {
+    /// Multiplying uniform values until the product drops below `exp(-lambda)`.
+    Multiplication { exp_neg_lambda: f64 },
+    /// Hörmann's transformed rejection with squeeze.
+    Ptrs {
+        a: f64,
+        b: f64,
+        ln_lambda: f64,
+        ln_inverse_alpha: f64,
+        v_r: f64,
+    },
+}
+impl Poisson {
+    /// Means at least this large use PTRS, and smaller ones the multiplication method.
+    const PTRS_THRESHOLD: f64 = 10.0;
+    /// Creates the distribution, which requires `lambda` to be finite and positive.
+    pub fn new(lambda: f64) -> Result<Self, ParameterError> {
+        let lambda = check_positive(lambda, "lambda")?;
+        let method = if lambda < Self::PTRS_THRESHOLD {
+            PoissonMethod::Multiplication {
+                exp_neg_lambda: (-lambda).exp(),
+            }
+        } else {
+            let b = 0.931 + 2.53 * lambda.sqrt();
+            PoissonMethod::Ptrs {
+                a: -0.059 + 0.02483 * b,
+                b,
+                ln_lambda: lambda.ln(),
+                ln_inverse_alpha: (1.1239 + 1.1328 / (b - 3.4)).ln(),
+                v_r: 0.9277 - 3.6224 / (b - 2.0),
+            }
+        };
+        Ok(Self { lambda, method })
+    }
+    /// Fills a slice of u64 with samples.
+    ///
+    /// Means below 10 use the multiplication method, taking about `lambda + 1` uniform
+    /// values per sample. Larger means use Hörmann's PTRS, which takes a little over two
+    /// uniform values per sample however large the mean is. Uniform values are drawn in
+    /// batches either way.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [u64],
+    ) {
+        let mut uniforms = WordBatch::new(rng);
+        match self.method {
+            PoissonMethod::Multiplication { exp_neg_lambda } => {
+                for value in destination_buffer {
+                    let mut count = 0;
+                    let mut product = 1.0 - uniforms.next_f64();
+                    while product > exp_neg_lambda {
+                        count += 1;
+                        product *= 1.0 - uniforms.next_f64();
+                    }
+                    *value = count;
+                }
+            }
+            PoissonMethod::Ptrs {
+                a,
+                b,
+                ln_lambda,
+                ln_inverse_alpha,
+                v_r,
+            } => {
+                for value in destination_buffer {
+                    *value = loop {
+                        let u = uniforms.next_f64() - 0.5;
+                        let v = uniforms.next_f64();
+                        let us = 0.5 - u.abs();
+                        let k = ((2.0 * a / us + b) * u + self.lambda + 0.43).floor();
+                        if us >= 0.07 && v <= v_r {
+                            break k as u64;
+                        }
+                        if k < 0.0 || (us < 0.013 && v > us) {
+                            continue;
+                        }
+                        let lhs = v.ln() + ln_inverse_alpha - (a / (us * us) + b).ln();
+                        let rhs = -self.lambda + k * ln_lambda - ln_gamma(k + 1.0);
+                        if lhs <= rhs {
+                            break k as u64;
+                        }
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// The binomial distribution, counting the successes among `n` independent trials that
+/// each succeed with probability `p`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Binomial {
+    n: u64,
+    // Samples are drawn with the smaller of `p` and `1 - p`, and flipped if needed
+    flipped: bool,
+    method: BinomialMethod,
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BinomialMethod {
+    /// Every sample is 0, or `n` when flipped.
+    Constant,
+    /// Inversion by sequential search from 0.
+    Inversion {
+        p: f64,
+        q: f64,
+        q_n: f64,
+        bound: f64,
+    },
+    /// Kachitvichyanukul and Schmeiser's BTPE.
+    Btpe(Btpe),
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Btpe {
+    n: f64,
+    r: f64,
+    q: f64,
+    nrq: f64,
+    m: f64,
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    xm: f64,
+    xl: f64,
+    xr: f64,
+    c: f64,
+    lambda_l: f64,
+    lambda_r: f64,
+}
+impl Binomial {
+    /// Means at least this large (after flipping `p` past 0.5) use BTPE, and smaller ones inversion.
+    const BTPE_THRESHOLD: f64 = 30.0;
+    /// Creates the distribution, which requires `p` to be in `[0, 1]`.
+    pub fn new(n: u64, p: f64) -> Result<Self, ParameterError> {
+        let p = check_probability(p, "p")?;
+        let flipped = p > 0.5;
+        let r = p.min(1.0 - p);
+        let q = 1.0 - r;
+        let n_f64 = n as f64;
+        let mean = n_f64 * r;
+        let method = if n == 0 || r == 0.0 {
+            BinomialMethod::Constant
+        } else if mean < Self::BTPE_THRESHOLD {
+            BinomialMethod::Inversion {
+                p: r,
+                q,
+                q_n: (n_f64 * (-r).ln_1p()).exp(),
+                bound: n_f64.min(mean + 10.0 * (mean * q + 1.0).sqrt()),
+            }
+        } else {
+            let fm = mean + r;
+            let m = fm.floor();
+            let p1 = (2.195 * (mean * q).sqrt() - 4.6 * q).floor() + 0.5;
+            let xm = m + 0.5;
+            let xl = xm - p1;
+            let xr = xm + p1;
+            let c = 0.134 + 20.5 / (15.3 + m);
+            let a = (fm - xl) / (fm - xl * r);
+            let lambda_l = a * (1.0 + a / 2.0);
+            let a = (xr - fm) / (xr * q);
+            let lambda_r = a * (1.0 + a / 2.0);
+            let p2 = p1 * (1.0 + 2.0 * c);
+            let p3 = p2 + c / lambda_l;
+            let p4 = p3 + c / lambda_r;
+            BinomialMethod::Btpe(Btpe {
+                n: n_f64,
+                r,
+                q,
+                nrq: mean * q,
+                m,
+                p1,
+                p2,
+                p3,
+                p4,
+                xm,
+                xl,
+                xr,
+                c,
+                lambda_l,
+                lambda_r,
+            })
+        };
+        Ok(Self { n, flipped, method })
+    }
+    /// Fills a slice of u64 with samples.
+    ///
+    /// Means below 30, taking `p` or `1 - p`, whichever is smaller, use inversion, taking one
+    /// uniform value per sample most of the time. Larger means use BTPE, which takes a little
+    /// over two uniform values per sample however large `n` is. Uniform values are drawn in
+    /// batches either way.
+    pub fn fill<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        destination_buffer: &mut [u64],
+    ) {
+        let mut uniforms = WordBatch::new(rng);
+        match &self.method {
+            BinomialMethod::Constant => destination_buffer.fill(0),
+            &BinomialMethod::Inversion { p, q, q_n, bound } => {
+                for value in destination_buffer.iter_mut() {
+                    let mut count = 0.0;
+                    let mut probability = q_n;
+                    let mut u = uniforms.next_f64();
+                    while u > probability {
+                        count += 1.0;
+                        if count > bound {
+                            // Rounding has left `u` beyond the cumulative probabilities, so start over
+                            count = 0.0;
+                            probability = q_n;
+                            u = uniforms.next_f64();
+                        } else {
+                            u -= probability;
+                            probability *= (self.n as f64 - count + 1.0) * p / (count * q);
+                        }
+                    }
+                    *value = count as u64;
+                }
+            }
+            BinomialMethod::Btpe(btpe) => {
+                for value in destination_buffer.iter_mut() {
+                    *value = btpe.sample(&mut uniforms);
+                }
+            }
+        }
+        if self.flipped {
+            for value in destination_buffer {
+                *value = self.n - *value;
+            }
+        }
+    }
+}
+impl Btpe {
+    /// Draws one sample, following the steps of Kachitvichyanukul and Schmeiser's paper.
+    fn sample<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &self,
+        uniforms: &mut WordBatch<'_, LANES, SHL_FIRST, SHL, SHR>,
+    ) -> u64 {
+        let &Self {
+            n,
+            r,
+            q,
+            nrq,
+            m,
+            p1,
+            p2,
+            p3,
+            p4,
+            xm,
+            xl,
+            xr,
+            c,
+            lambda_l,
+            lambda_r,
+        } = self;
+        loop {
+            let u = uniforms.next_f64() * p4;
+            let mut v = uniforms.next_f64();
+            // The triangular region in the middle accepts immediately
+            if u <= p1 {
+                return (xm - p1 * v + u).floor() as u64;
+            }
+            // Otherwise pick a point in the parallelograms or the exponential tails
+            let y = if u <= p2 {
+                let x = xl + (u - p1) / c;
+                v = v * c + 1.0 - (m - x + 0.5).abs() / p1;
+                if v > 1.0 {
+                    continue;
+                }
+                x.floor()
+            } else if u <= p3 {
+                let y = (xl + v.ln() / lambda_l).floor();
+                if y < 0.0 || v == 0.0 {
+                    continue;
+                }
+                v *= (u - p2) * lambda_l;
+                y
+            } else {
+                let y = (xr - v.ln() / lambda_r).floor();
+                if y > n || v == 0.0 {
+                    continue;
+                }
+                v *= (u - p3) * lambda_r;
+                y
+            };
+            let k = (y - m).abs();
+            if k <= 20.0 || k >= nrq / 2.0 - 1.0 {
+                // Evaluate the ratio of probabilities f(y) / f(m) directly
+                let s = r / q;
+                let a = s * (n + 1.0);
+                let mut ratio = 1.0;
+                if m < y {
+                    let mut i = m + 1.0;
+                    while i <= y {
+                        ratio *= a / i - s;
+                        i += 1.0;
+                    }
+                } else if m > y {
+                    let mut i = y + 1.0;
+                    while i <= m {
+                        ratio /= a / i - s;
+                        i += 1.0;
+                    }
+                }
+                if v > ratio {
+                    continue;
+                }
+                return y as u64;
+            }
+            // Squeeze with bounds on ln(f(y) / f(m)), before the final Stirling-based test
+            let rho = (k / nrq) * ((k * (k / 3.0 + 0.625) + 1.0 / 6.0) / nrq + 0.5);
+            let t = -k * k / (2.0 * nrq);
+            let ln_v = v.ln();
+            if ln_v < t - rho {
+                return y as u64;
+            }
+            if ln_v > t + rho {
+                continue;
+            }
+            let x1 = y + 1.0;
+            let f1 = m + 1.0;
+            let z = n + 1.0 - m;
+            let w = n - y + 1.0;
+            let stirling = |x: f64| {
+                let x2 = x * x;
+                (13860.0 - (462.0 - (132.0 - (99.0 - 140.0 / x2) / x2) / x2) / x2) / x / 166320.0
+            };
+            // The paper adds all four correction terms, which is an erratum: the terms for
+            // y's factorials enter with the opposite sign, as GSL and rand_distr have them
+            let bound = xm * (f1 / x1).ln()
+                + (n - m + 0.5) * ((y - m) / w).ln_1p()
+                + (y - m) * (w * r / (x1 * q)).ln()
+                + stirling(f1)
+                + stirling(z)
+                - stirling(x1)
+                - stirling(w);
+            if ln_v > bound {
+                continue;
+            }
+            return y as u64;
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::ShiftConfig;
+use core::fmt;
+
+/// The reason a prng state was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StateError {
+    /// Both of a lane's state words were zero.
+    ZeroLane { lane: usize },
+    /// Both of a lane's state words were the same.
+    EqualWordsInLane { lane: usize },
+    /// Two lanes had the same value in one of their state words.
+    DuplicateLanes { first: usize, second: usize },
+}
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroLane { lane } => write!(f, "both state words of lane {lane} are zero"),
+            Self::EqualWordsInLane { lane } => {
+                write!(f, "both state words of lane {lane} are equal")
+            }
+            Self::DuplicateLanes { first, second } => {
+                write!(f, "lanes {first} and {second} share a state word")
+            }
+        }
+    }
+}
+impl std::error::Error for StateError {}
+
+/// The reason a checkpoint couldn't be restored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CheckpointError {
+    /// The checkpoint was shorter or longer than its header says it should be.
+    WrongLength,
+    /// The checkpoint didn't start with the expected magic number.
+    BadMagic,
+    /// The checksum didn't match the checkpoint's contents.
+    ChecksumMismatch,
+    /// The checkpoint was written in a format version this build can't read.
+    UnsupportedVersion(u32),
+    /// The checkpoint was written by a prng with a different number of lanes.
+    LaneMismatch { expected: usize, found: u32 },
+    /// The checkpoint was written by a prng with a different shift configuration.
+    ShiftMismatch {
+        expected: ShiftConfig,
+        found: ShiftConfig,
+    },
+    /// The checkpoint's state would have been rejected when seeding.
+    InvalidState(StateError),
+}
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongLength => write!(f, "checkpoint has the wrong length"),
+            Self::BadMagic => write!(f, "not an xorshiftr-wide checkpoint"),
+            Self::ChecksumMismatch => write!(f, "checkpoint checksum mismatch"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported checkpoint version {version}")
+            }
+            Self::LaneMismatch { expected, found } => {
+                write!(f, "checkpoint has {found} lanes, expected {expected}")
+            }
+            Self::ShiftMismatch { expected, found } => write!(
+                f,
+                "checkpoint has shift configuration {found:?}, expected {expected:?}"
+            ),
+            Self::InvalidState(error) => write!(f, "checkpoint has an invalid state: {error}"),
+        }
+    }
+}
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidState(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+impl From<StateError> for CheckpointError {
+    fn from(error: StateError) -> Self {
+        Self::InvalidState(error)
+    }
+}
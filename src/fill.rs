@@ -0,0 +1,474 @@
+use crate::*;
+use core::ops::{Range, RangeInclusive};
+
+// Scales for turning the high bits of a word into a float
+pub(crate) const F64_SCALE: f64 = 1.0 / (1u64 << 53) as f64;
+const F64_HALF_STEP_SCALE: f64 = 1.0 / (1u64 << 52) as f64;
+pub(crate) const F32_SCALE: f32 = 1.0 / (1u32 << 24) as f32;
+const F32_HALF_STEP_SCALE: f32 = 1.0 / (1u32 << 23) as f32;
+
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>
+{
+    /// Fills `buffer` with values built from the u64 stream, `N` values per word, taking
+    /// words in the same order as `fill_core`. A short tail takes the start of one more block.
+    ///
+    /// Words are generated a few blocks at a time into a scratch buffer by the fastest kernel
+    /// the CPU supports, then mapped into `buffer`.
+    #[inline(always)]
+    pub(crate) fn fill_mapped<T: Copy, const N: usize>(
+        &mut self,
+        buffer: &mut [T],
+        map: impl Fn(u64) -> [T; N],
+    ) {
+        const BLOCKS_PER_MAP: usize = 8;
+        let kernel = Kernel::detect();
+        let mut temporary_buffer = [[0u64; LANES]; BLOCKS_PER_MAP];
+        let temporary_buffer = temporary_buffer.as_flattened_mut();
+        for chunk in buffer.chunks_mut(temporary_buffer.len() * N) {
+            let words = &mut temporary_buffer[..chunk.len().div_ceil(N)];
+            self.fill_core_with_kernel(kernel, words);
+            let (values, tail) = chunk.as_chunks_mut::<N>();
+            for (values, &word) in values.iter_mut().zip(words.iter()) {
+                *values = map(word);
+            }
+            if !tail.is_empty() {
+                let last_word = words[words.len() - 1];
+                tail.copy_from_slice(&map(last_word)[..tail.len()]);
+            }
+        }
+    }
+    /// Fills a slice of f64 with random bit patterns, as `fill_core` would fill a slice of u64,
+    /// for the caller to read back with `to_bits` and convert.
+    #[inline(always)]
+    pub(crate) fn fill_f64_bits(&mut self, destination_buffer: &mut [f64]) {
+        // SAFETY: f64 has the same size and alignment as u64, and every bit pattern is a valid u64
+        let words: &mut [u64] = unsafe {
+            core::slice::from_raw_parts_mut(
+                destination_buffer.as_mut_ptr().cast(),
+                destination_buffer.len(),
+            )
+        };
+        self.fill_core(words);
+    }
+    /// Fills a slice of f64 in place, by filling it with random words and converting each one.
+    #[inline(always)]
+    fn fill_f64_with(&mut self, destination_buffer: &mut [f64], convert: impl Fn(u64) -> f64) {
+        self.fill_f64_bits(destination_buffer);
+        for value in destination_buffer {
+            *value = convert(value.to_bits());
+        }
+    }
+    /// Fills a slice of f64 with uniform random values in `[0, 1)`,
+    /// using the high 53 bits of each u64 of output.
+    pub fn fill_f64(&mut self, destination_buffer: &mut [f64]) {
+        self.fill_f64_with(destination_buffer, |word| (word >> 11) as f64 * F64_SCALE);
+    }
+    /// Fills a slice of f64 with uniform random values in `(0, 1]`,
+    /// using the high 53 bits of each u64 of output.
+    pub fn fill_f64_open_closed(&mut self, destination_buffer: &mut [f64]) {
+        self.fill_f64_with(destination_buffer, |word| {
+            ((word >> 11) + 1) as f64 * F64_SCALE
+        });
+    }
+    /// Fills a slice of f64 with uniform random values in `(0, 1)`,
+    /// using the high 52 bits of each u64 of output, offset by half a step.
+    pub fn fill_f64_open(&mut self, destination_buffer: &mut [f64]) {
+        self.fill_f64_with(destination_buffer, |word| {
+            ((word >> 12) as f64 + 0.5) * F64_HALF_STEP_SCALE
+        });
+    }
+    /// Fills a slice of f32 with uniform random values in `[0, 1)`.
+    ///
+    /// Each u64 of output makes two values, from the high 24 bits of its low half
+    /// and then of its high half.
+    pub fn fill_f32(&mut self, destination_buffer: &mut [f32]) {
+        self.fill_mapped(destination_buffer, |word| {
+            split_u32(word).map(|half| (half >> 8) as f32 * F32_SCALE)
+        });
+    }
+    /// Fills a slice of f32 with uniform random values in `(0, 1]`,
+    /// taking two values from each u64 of output like [`Self::fill_f32`].
+    pub fn fill_f32_open_closed(&mut self, destination_buffer: &mut [f32]) {
+        self.fill_mapped(destination_buffer, |word| {
+            split_u32(word).map(|half| ((half >> 8) + 1) as f32 * F32_SCALE)
+        });
+    }
+    /// Fills a slice of f32 with uniform random values in `(0, 1)`,
+    /// taking two values from each u64 of output like [`Self::fill_f32`],
+    /// using 23 bits per value offset by half a step.
+    pub fn fill_f32_open(&mut self, destination_buffer: &mut [f32]) {
+        self.fill_mapped(destination_buffer, |word| {
+            split_u32(word).map(|half| ((half >> 9) as f32 + 0.5) * F32_HALF_STEP_SCALE)
+        });
+    }
+    /// Fills a slice of u64 with random bits that are each set independently with probability `p`,
+    /// like [`Self::fill_bernoulli_bits_with_precision`] with enough bits to keep 32 significant
+    /// digits of `p` or `1 - p`, whichever is smaller.
+    ///
+    /// That's a precision of 2^-32 or finer, down to 2^-64 as `p` nears 0 or 1.
+    /// A `p` below 2^-65 rounds to 0, so it fills the slice with zeros.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` isn't between 0 and 1.
+    pub fn fill_bernoulli_bits(&mut self, destination_buffer: &mut [u64], p: f64) {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        // The position of the first set binary digit, which is infinite for p = 0 or 1
+        let leading_digit = (-p.min(1.0 - p).log2()).ceil() as u32;
+        let precision_bits = leading_digit.saturating_add(31).min(64);
+        if (p * 2f64.powi(64)).round() == 0.0 {
+            destination_buffer.fill(0);
+        } else {
+            self.fill_bernoulli_bits_with_precision(destination_buffer, p, precision_bits);
+        }
+    }
+    /// Fills a slice of u64 with random bits that are each set independently with probability `p`,
+    /// rounded to the nearest multiple of 2^-`precision_bits`.
+    ///
+    /// Each word of the result combines one u64 of output per binary digit of `p`, from its last
+    /// set digit up to its first, so `p = 0.5` takes one u64 per word, and a `p` that needs every
+    /// digit takes `precision_bits`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision_bits` isn't between 1 and 64, if `p` isn't between 0 and 1, or if
+    /// `p` would round to exactly 0 or 1 without being exactly 0 or 1.
+    pub fn fill_bernoulli_bits_with_precision(
+        &mut self,
+        destination_buffer: &mut [u64],
+        p: f64,
+        precision_bits: u32,
+    ) {
+        const BLOCKS_PER_CHUNK: usize = 8;
+        assert!(
+            (1..=64).contains(&precision_bits),
+            "precision must be between 1 and 64 bits"
+        );
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        if p == 0.0 {
+            destination_buffer.fill(0);
+            return;
+        } else if p == 1.0 {
+            destination_buffer.fill(u64::MAX);
+            return;
+        }
+        let numerator = (p * 2f64.powi(precision_bits as i32)).round() as u128;
+        assert!(
+            numerator != 0 && numerator != 1 << precision_bits,
+            "p = {p} can't be told apart from 0 or 1 with {precision_bits} bits of precision"
+        );
+        let numerator = numerator as u64;
+        // After the lowest set digit, which is always 1, come the digits above it
+        let lowest_digit = numerator.trailing_zeros();
+        let digits = numerator >> lowest_digit;
+        let digit_count = precision_bits - lowest_digit;
+        let mut temporary_buffer = [[0u64; LANES]; BLOCKS_PER_CHUNK];
+        let temporary_buffer = temporary_buffer.as_flattened_mut();
+        for chunk in destination_buffer.chunks_mut(temporary_buffer.len()) {
+            // Starting from probability 0, OR with fresh bits to append a 1 digit to the
+            // front of the probability, or AND to append a 0
+            self.fill_core(chunk);
+            let words = &mut temporary_buffer[..chunk.len()];
+            for digit in 1..digit_count {
+                self.fill_core(words);
+                if digits >> digit & 1 == 1 {
+                    for (value, &word) in chunk.iter_mut().zip(words.iter()) {
+                        *value |= word;
+                    }
+                } else {
+                    for (value, &word) in chunk.iter_mut().zip(words.iter()) {
+                        *value &= word;
+                    }
+                }
+            }
+        }
+    }
+    /// Fills a slice of u32 with random data.
+    ///
+    /// Each u64 of output makes two values, its low half and then its high half,
+    /// so the output matches [`Self::fill_bytes`] read as little-endian u32s on every target.
+    pub fn fill_u32(&mut self, destination_buffer: &mut [u32]) {
+        self.fill_mapped(destination_buffer, split_u32);
+    }
+    /// Fills a slice of u16 with random data.
+    ///
+    /// Each u64 of output makes four values, from its lowest 16 bits to its highest,
+    /// so the output matches [`Self::fill_bytes`] read as little-endian u16s on every target.
+    pub fn fill_u16(&mut self, destination_buffer: &mut [u16]) {
+        self.fill_mapped(destination_buffer, |word| {
+            [
+                word as u16,
+                (word >> 16) as u16,
+                (word >> 32) as u16,
+                (word >> 48) as u16,
+            ]
+        });
+    }
+    /// Fills a slice of u128 with random data.
+    ///
+    /// Each value takes two consecutive u64s of output, the first as its low half,
+    /// so the output matches [`Self::fill_bytes`] read as little-endian u128s on every target.
+    pub fn fill_u128(&mut self, destination_buffer: &mut [u128]) {
+        // SAFETY: u128 is at least as aligned as u64 and twice its size,
+        // and every bit pattern is a valid u64
+        let words: &mut [u64] = unsafe {
+            core::slice::from_raw_parts_mut(
+                destination_buffer.as_mut_ptr().cast(),
+                destination_buffer.len() * 2,
+            )
+        };
+        self.fill_core(words);
+        for value in destination_buffer {
+            // Words were written in native order, so put the first one in the low half
+            // SAFETY: u128 and [u64; 2] have the same size, and every bit pattern is valid for both
+            let [first, second] = unsafe { core::mem::transmute::<u128, [u64; 2]>(*value) };
+            *value = first as u128 | (second as u128) << 64;
+        }
+    }
+    /// Fills a slice of i8 with random data, in the same order as [`Self::fill_bytes`].
+    pub fn fill_i8(&mut self, destination_buffer: &mut [i8]) {
+        self.fill_bytes(as_unsigned(destination_buffer));
+    }
+    /// Fills a slice of i16 with random data, in the same order as [`Self::fill_u16`].
+    pub fn fill_i16(&mut self, destination_buffer: &mut [i16]) {
+        self.fill_u16(as_unsigned(destination_buffer));
+    }
+    /// Fills a slice of i32 with random data, in the same order as [`Self::fill_u32`].
+    pub fn fill_i32(&mut self, destination_buffer: &mut [i32]) {
+        self.fill_u32(as_unsigned(destination_buffer));
+    }
+    /// Fills a slice of i64 with random data, in the same order as [`Self::fill_u64_buffer`].
+    pub fn fill_i64(&mut self, destination_buffer: &mut [i64]) {
+        self.fill_core(as_unsigned(destination_buffer));
+    }
+    /// Fills a slice of i128 with random data, in the same order as [`Self::fill_u128`].
+    pub fn fill_i128(&mut self, destination_buffer: &mut [i128]) {
+        self.fill_u128(as_unsigned(destination_buffer));
+    }
+    /// Fills a slice of u64 with uniform random values in `[low, low + span)`, wrapping,
+    /// where a `span` of 0 stands for all 2^64 values.
+    ///
+    /// This uses Lemire's multiply-and-reject method in two passes. The first pass converts a
+    /// whole buffer of output, marking the rare rejected values, and the second replaces them.
+    fn fill_span_u64(&mut self, buffer: &mut [u64], low: u64, span: u64) {
+        self.fill_core(buffer);
+        if span != 0 {
+            // The high word of `word * span` is uniform in `[0, span)` unless the low word
+            // falls below `2^64 mod span`, which happens with probability below `span / 2^64`.
+            // Accepted values are below `span`, so `u64::MAX` is free to mark rejections.
+            let threshold = span.wrapping_neg() % span;
+            let mut any_rejected = false;
+            for value in buffer.iter_mut() {
+                let product = *value as u128 * span as u128;
+                let rejected = (product as u64) < threshold;
+                any_rejected |= rejected;
+                *value = if rejected {
+                    u64::MAX
+                } else {
+                    (product >> 64) as u64
+                };
+            }
+            if any_rejected {
+                cold();
+                let mut block = [0u64; LANES];
+                let mut position = LANES;
+                for value in buffer.iter_mut().filter(|value| **value == u64::MAX) {
+                    *value = loop {
+                        if position == LANES {
+                            self.next_block(&mut block);
+                            position = 0;
+                        }
+                        let product = block[position] as u128 * span as u128;
+                        position += 1;
+                        if (product as u64) >= threshold {
+                            break (product >> 64) as u64;
+                        }
+                    };
+                }
+            }
+        }
+        if low != 0 {
+            for value in buffer {
+                *value = value.wrapping_add(low);
+            }
+        }
+    }
+    /// Fills a slice of u32 with uniform random values in `[low, low + span)`, wrapping,
+    /// where a `span` of 0 stands for all 2^32 values.
+    ///
+    /// This works like `fill_span_u64`, on each half of each u64 of output.
+    fn fill_span_u32(&mut self, buffer: &mut [u32], low: u32, span: u32) {
+        self.fill_mapped(buffer, split_u32);
+        if span != 0 {
+            let threshold = span.wrapping_neg() % span;
+            let mut any_rejected = false;
+            for value in buffer.iter_mut() {
+                let product = *value as u64 * span as u64;
+                let rejected = (product as u32) < threshold;
+                any_rejected |= rejected;
+                *value = if rejected {
+                    u32::MAX
+                } else {
+                    (product >> 32) as u32
+                };
+            }
+            if any_rejected {
+                cold();
+                let mut block = [0u64; LANES];
+                let mut position = 2 * LANES;
+                for value in buffer.iter_mut().filter(|value| **value == u32::MAX) {
+                    *value = loop {
+                        if position == 2 * LANES {
+                            self.next_block(&mut block);
+                            position = 0;
+                        }
+                        let half = split_u32(block[position / 2])[position % 2];
+                        let product = half as u64 * span as u64;
+                        position += 1;
+                        if (product as u32) >= threshold {
+                            break (product >> 32) as u32;
+                        }
+                    };
+                }
+            }
+        }
+        if low != 0 {
+            for value in buffer {
+                *value = value.wrapping_add(low);
+            }
+        }
+    }
+    /// Fills a slice of u64 with unbiased uniform random values in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn fill_range_u64(&mut self, destination_buffer: &mut [u64], range: Range<u64>) {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        self.fill_span_u64(destination_buffer, range.start, range.end - range.start);
+    }
+    /// Fills a slice of u64 with unbiased uniform random values in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn fill_range_inclusive_u64(
+        &mut self,
+        destination_buffer: &mut [u64],
+        range: RangeInclusive<u64>,
+    ) {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        let (low, high) = range.into_inner();
+        self.fill_span_u64(destination_buffer, low, (high - low).wrapping_add(1));
+    }
+    /// Fills a slice of i64 with unbiased uniform random values in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn fill_range_i64(&mut self, destination_buffer: &mut [i64], range: Range<i64>) {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        let span = range.end.wrapping_sub(range.start) as u64;
+        self.fill_span_u64(as_unsigned(destination_buffer), range.start as u64, span);
+    }
+    /// Fills a slice of i64 with unbiased uniform random values in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn fill_range_inclusive_i64(
+        &mut self,
+        destination_buffer: &mut [i64],
+        range: RangeInclusive<i64>,
+    ) {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        let (low, high) = range.into_inner();
+        let span = (high.wrapping_sub(low) as u64).wrapping_add(1);
+        self.fill_span_u64(as_unsigned(destination_buffer), low as u64, span);
+    }
+    /// Fills a slice of u32 with unbiased uniform random values in `range`.
+    ///
+    /// Each u64 of output makes two candidate values, from its low half and then its high half.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn fill_range_u32(&mut self, destination_buffer: &mut [u32], range: Range<u32>) {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        self.fill_span_u32(destination_buffer, range.start, range.end - range.start);
+    }
+    /// Fills a slice of u32 with unbiased uniform random values in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn fill_range_inclusive_u32(
+        &mut self,
+        destination_buffer: &mut [u32],
+        range: RangeInclusive<u32>,
+    ) {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        let (low, high) = range.into_inner();
+        self.fill_span_u32(destination_buffer, low, (high - low).wrapping_add(1));
+    }
+    /// Fills a slice of i32 with unbiased uniform random values in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn fill_range_i32(&mut self, destination_buffer: &mut [i32], range: Range<i32>) {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        let span = range.end.wrapping_sub(range.start) as u32;
+        self.fill_span_u32(as_unsigned(destination_buffer), range.start as u32, span);
+    }
+    /// Fills a slice of i32 with unbiased uniform random values in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn fill_range_inclusive_i32(
+        &mut self,
+        destination_buffer: &mut [i32],
+        range: RangeInclusive<i32>,
+    ) {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        let (low, high) = range.into_inner();
+        let span = (high.wrapping_sub(low) as u32).wrapping_add(1);
+        self.fill_span_u32(as_unsigned(destination_buffer), low as u32, span);
+    }
+}
+
+/// Signed integers that share their layout with an unsigned type.
+pub(crate) trait Signed {
+    type Unsigned;
+}
+impl Signed for i8 {
+    type Unsigned = u8;
+}
+impl Signed for i16 {
+    type Unsigned = u16;
+}
+impl Signed for i32 {
+    type Unsigned = u32;
+}
+impl Signed for i64 {
+    type Unsigned = u64;
+}
+impl Signed for i128 {
+    type Unsigned = u128;
+}
+
+/// Views a slice of signed integers as their unsigned counterparts.
+#[inline(always)]
+pub(crate) fn as_unsigned<T: Signed>(values: &mut [T]) -> &mut [T::Unsigned] {
+    // SAFETY: each signed integer has the same size and alignment as its unsigned counterpart,
+    // and every bit pattern is valid for both
+    unsafe { core::slice::from_raw_parts_mut(values.as_mut_ptr().cast(), values.len()) }
+}
+
+/// Splits a word into its low and high halves, in that order.
+#[inline(always)]
+pub(crate) fn split_u32(word: u64) -> [u32; 2] {
+    [word as u32, (word >> 32) as u32]
+}
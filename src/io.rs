@@ -0,0 +1,145 @@
+use crate::*;
+use std::io::{self, BufRead, Read, Write};
+
+/// An endless (or optionally limited) stream of random bytes, implementing [`Read`] and
+/// [`BufRead`].
+///
+/// The bytes come in the same order as [`XorshiftrWideWith::fill_bytes`], so reading `n` bytes
+/// from a new reader gives the same as filling `n` bytes from the prng it wraps, however the
+/// reads are split up.
+#[derive(Clone, Debug)]
+pub struct RandomReader<
+    const LANES: usize = 16,
+    const SHL_FIRST: bool = DEFAULT_SHL_FIRST,
+    const SHL: u32 = DEFAULT_SHL,
+    const SHR: u32 = DEFAULT_SHR,
+> {
+    rng: XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+    // Whole blocks of output, each word already converted to little-endian
+    blocks: [[u64; LANES]; BLOCKS_PER_READ],
+    // Index of the next unread byte in `blocks`
+    position: usize,
+    // How many more bytes may be read, if the stream is limited
+    remaining: Option<u64>,
+}
+
+/// How many blocks a reader generates at a time.
+const BLOCKS_PER_READ: usize = 8;
+
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    RandomReader<LANES, SHL_FIRST, SHL, SHR>
+{
+    const BUFFER_BYTES: usize = LANES * BLOCKS_PER_READ * 8;
+    /// Wraps a prng instance in an endless stream.
+    pub fn new(rng: XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>) -> Self {
+        Self {
+            rng,
+            blocks: [[0; LANES]; BLOCKS_PER_READ],
+            position: Self::BUFFER_BYTES,
+            remaining: None,
+        }
+    }
+    /// Wraps a prng instance in a stream that ends after `limit` bytes.
+    pub fn with_limit(rng: XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>, limit: u64) -> Self {
+        Self {
+            remaining: Some(limit),
+            ..Self::new(rng)
+        }
+    }
+    /// Returns how many more bytes can be read, or `None` if the stream is endless.
+    pub fn remaining(&self) -> Option<u64> {
+        self.remaining
+    }
+    /// Unwraps the inner prng instance, discarding any buffered output.
+    pub fn into_inner(self) -> XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR> {
+        self.rng
+    }
+    /// The buffered blocks, viewed as bytes.
+    fn buffer(&self) -> &[u8] {
+        // SAFETY: the blocks are `BUFFER_BYTES` long, and any memory is valid as u8
+        unsafe { core::slice::from_raw_parts(self.blocks.as_ptr().cast(), Self::BUFFER_BYTES) }
+    }
+    /// Caps a length at the number of bytes left in a limited stream.
+    fn limit(&self, len: usize) -> usize {
+        match self.remaining {
+            Some(remaining) => len.min(remaining.try_into().unwrap_or(usize::MAX)),
+            None => len,
+        }
+    }
+}
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32> Read
+    for RandomReader<LANES, SHL_FIRST, SHL, SHR>
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.limit(buf.len());
+        // With the buffer used up, whole blocks can go straight into a large enough read
+        let direct_len = len / (LANES * 8) * (LANES * 8);
+        if self.position == Self::BUFFER_BYTES && direct_len != 0 {
+            self.rng.fill_bytes(&mut buf[..direct_len]);
+            if let Some(remaining) = &mut self.remaining {
+                *remaining -= direct_len as u64;
+            }
+            return Ok(direct_len);
+        }
+        let available = self.fill_buf()?;
+        let len = len.min(available.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32> BufRead
+    for RandomReader<LANES, SHL_FIRST, SHL, SHR>
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position == Self::BUFFER_BYTES && self.remaining != Some(0) {
+            let words = self.blocks.as_flattened_mut();
+            self.rng.fill_core(words);
+            for word in words {
+                *word = word.to_le();
+            }
+            self.position = 0;
+        }
+        let len = self.limit(Self::BUFFER_BYTES - self.position);
+        Ok(&self.buffer()[self.position..][..len])
+    }
+    fn consume(&mut self, amount: usize) {
+        let amount = amount.min(self.limit(Self::BUFFER_BYTES - self.position));
+        self.position += amount;
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= amount as u64;
+        }
+    }
+}
+
+/// Writes `n_bytes` random bytes to `sink`, leaving `rng` as if it had filled them with
+/// [`XorshiftrWideWith::fill_bytes`], and writing the same bytes.
+///
+/// The bytes are generated into one buffer of about 64 KiB, a whole number of blocks, which is
+/// reused for every write, so the memory used doesn't grow with `n_bytes`.
+pub fn write_random<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+    rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+    sink: &mut impl Write,
+    n_bytes: u64,
+) -> io::Result<()> {
+    const TARGET_BYTES: usize = 1 << 16;
+    // Short outputs only need enough blocks to cover them
+    let blocks_per_write = (TARGET_BYTES / (LANES * 8)).max(1) as u64;
+    let blocks_per_write = blocks_per_write.min(n_bytes.div_ceil(LANES as u64 * 8)) as usize;
+    let mut buffer = vec![[0u64; LANES]; blocks_per_write];
+    let buffer = buffer.as_flattened_mut();
+    let mut remaining = n_bytes;
+    while remaining != 0 {
+        let len = remaining.min(buffer.len() as u64 * 8) as usize;
+        let words = &mut buffer[..len.div_ceil(8)];
+        rng.fill_core(words);
+        for word in words.iter_mut() {
+            *word = word.to_le();
+        }
+        // SAFETY: `words` is at least `len` bytes long, and any memory is valid as u8
+        let bytes: &[u8] = unsafe { core::slice::from_raw_parts(words.as_ptr().cast(), len) };
+        sink.write_all(bytes)?;
+        remaining -= len as u64;
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::*;
+
+/// An implementation of the lane update used for filling buffers.
+///
+/// Every kernel produces bit-identical output. By default, the best kernel the running
+/// CPU supports is chosen at runtime, so binaries built without a `target-cpu` still use
+/// wide vector instructions where they're available.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Kernel {
+    /// Plain Rust, left to the compiler to autovectorize for the target it was built for.
+    Portable,
+    /// Explicit AVX2 instructions, handling 4 lanes per vector. Used when `LANES` is a
+    /// multiple of 4.
+    Avx2,
+    /// Explicit AVX-512 instructions, handling 8 lanes per vector. Used when `LANES` is a
+    /// multiple of 8, falling back to AVX2 when it's only a multiple of 4.
+    Avx512,
+}
+impl Kernel {
+    /// Returns the fastest kernel the running CPU supports.
+    pub fn detect() -> Self {
+        if Self::Avx512.is_supported() {
+            Self::Avx512
+        } else if Self::Avx2.is_supported() {
+            Self::Avx2
+        } else {
+            Self::Portable
+        }
+    }
+    /// Returns whether the running CPU supports this kernel.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::Portable => true,
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx2 => std::arch::is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx512 => {
+                std::arch::is_x86_feature_detected!("avx512f")
+                    && std::arch::is_x86_feature_detected!("avx2")
+            }
+            #[cfg(not(target_arch = "x86_64"))]
+            Self::Avx2 | Self::Avx512 => false,
+        }
+    }
+}
+
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>
+{
+    /// Fills a buffer whose length is a multiple of `LANES` with whole blocks,
+    /// using `kernel`, which the caller must have checked is supported.
+    #[inline(always)]
+    pub(crate) fn fill_blocks(&mut self, kernel: Kernel, blocks: &mut [u64]) {
+        debug_assert!(blocks.len().is_multiple_of(LANES));
+        debug_assert!(kernel.is_supported());
+        #[cfg(target_arch = "x86_64")]
+        match kernel {
+            // SAFETY: the caller checked the CPU supports the kernel
+            Kernel::Avx512 if LANES.is_multiple_of(8) => {
+                return unsafe {
+                    x86::fill_avx512::<LANES, SHL_FIRST, SHL, SHR>(&mut self.state, blocks)
+                };
+            }
+            Kernel::Avx2 | Kernel::Avx512 if LANES.is_multiple_of(4) => {
+                return unsafe {
+                    x86::fill_avx2::<LANES, SHL_FIRST, SHL, SHR>(&mut self.state, blocks)
+                };
+            }
+            _ => {}
+        }
+        for chunk in blocks.chunks_exact_mut(LANES) {
+            let chunk_as_array: &mut [u64; LANES] = unsafe { chunk.try_into().unwrap_unchecked() };
+            self.next_block(chunk_as_array);
+        }
+    }
+    /// Fills a slice of u64 with random data using a specific kernel.
+    ///
+    /// The output is the same as [`Self::fill_u64_buffer`], which picks the kernel itself;
+    /// this is useful for benchmarking kernels against each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the running CPU doesn't support `kernel`.
+    pub fn fill_u64_buffer_with_kernel(&mut self, kernel: Kernel, destination_buffer: &mut [u64]) {
+        assert!(
+            kernel.is_supported(),
+            "{kernel:?} kernel is not supported by this CPU"
+        );
+        self.fill_core_with_kernel(kernel, destination_buffer);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::*;
+
+    // Kernels keep each group of lanes in registers while they work through a tile of blocks,
+    // small enough to stay in L1 cache before moving on to the next group of lanes.
+    const TILE_BLOCKS: usize = 32;
+
+    /// # Safety
+    ///
+    /// The CPU must support AVX2, `LANES` must be a multiple of 4,
+    /// and `blocks.len()` must be a multiple of `LANES`.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn fill_avx2<
+        const LANES: usize,
+        const SHL_FIRST: bool,
+        const SHL: u32,
+        const SHR: u32,
+    >(
+        state: &mut [[u64; LANES]; 2],
+        blocks: &mut [u64],
+    ) {
+        let shl = _mm_cvtsi32_si128(SHL as i32);
+        let shr = _mm_cvtsi32_si128(SHR as i32);
+        for tile in blocks.chunks_mut(LANES * TILE_BLOCKS) {
+            let tile_blocks = tile.len() / LANES;
+            for group in (0..LANES).step_by(4) {
+                // SAFETY: `group + 4 <= LANES`, and every block in the tile has `LANES` words
+                unsafe {
+                    let top = state[0].as_mut_ptr().add(group).cast::<__m256i>();
+                    let bottom = state[1].as_mut_ptr().add(group).cast::<__m256i>();
+                    let mut s0 = _mm256_loadu_si256(top);
+                    let mut s1 = _mm256_loadu_si256(bottom);
+                    let out = tile.as_mut_ptr().add(group);
+                    for block in 0..tile_blocks {
+                        let mut x = s0;
+                        let y = s1;
+                        s0 = y;
+                        if SHL_FIRST {
+                            x = _mm256_xor_si256(x, _mm256_sll_epi64(x, shl));
+                            x = _mm256_xor_si256(x, _mm256_srl_epi64(x, shr));
+                        } else {
+                            x = _mm256_xor_si256(x, _mm256_srl_epi64(x, shr));
+                            x = _mm256_xor_si256(x, _mm256_sll_epi64(x, shl));
+                        }
+                        s1 = _mm256_add_epi64(x, y);
+                        _mm256_storeu_si256(out.add(block * LANES).cast(), x);
+                    }
+                    _mm256_storeu_si256(top, s0);
+                    _mm256_storeu_si256(bottom, s1);
+                }
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support AVX-512F, `LANES` must be a multiple of 8,
+    /// and `blocks.len()` must be a multiple of `LANES`.
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn fill_avx512<
+        const LANES: usize,
+        const SHL_FIRST: bool,
+        const SHL: u32,
+        const SHR: u32,
+    >(
+        state: &mut [[u64; LANES]; 2],
+        blocks: &mut [u64],
+    ) {
+        let shl = _mm_cvtsi32_si128(SHL as i32);
+        let shr = _mm_cvtsi32_si128(SHR as i32);
+        for tile in blocks.chunks_mut(LANES * TILE_BLOCKS) {
+            let tile_blocks = tile.len() / LANES;
+            for group in (0..LANES).step_by(8) {
+                // SAFETY: `group + 8 <= LANES`, and every block in the tile has `LANES` words
+                unsafe {
+                    let top = state[0].as_mut_ptr().add(group).cast::<__m512i>();
+                    let bottom = state[1].as_mut_ptr().add(group).cast::<__m512i>();
+                    let mut s0 = _mm512_loadu_si512(top);
+                    let mut s1 = _mm512_loadu_si512(bottom);
+                    let out = tile.as_mut_ptr().add(group);
+                    for block in 0..tile_blocks {
+                        let mut x = s0;
+                        let y = s1;
+                        s0 = y;
+                        if SHL_FIRST {
+                            x = _mm512_xor_si512(x, _mm512_sll_epi64(x, shl));
+                            x = _mm512_xor_si512(x, _mm512_srl_epi64(x, shr));
+                        } else {
+                            x = _mm512_xor_si512(x, _mm512_srl_epi64(x, shr));
+                            x = _mm512_xor_si512(x, _mm512_sll_epi64(x, shl));
+                        }
+                        s1 = _mm512_add_epi64(x, y);
+                        _mm512_storeu_si512(out.add(block * LANES).cast(), x);
+                    }
+                    _mm512_storeu_si512(top, s0);
+                    _mm512_storeu_si512(bottom, s1);
+                }
+            }
+        }
+    }
+}
@@ -1,98 +1,355 @@
+pub mod distributions;
+
+pub use buffered::BufferedXorshiftrWide;
+pub use error::{CheckpointError, StateError};
+pub use io::{RandomReader, write_random};
+pub use kernels::Kernel;
+pub use permutation::RandomPermutation;
+pub use scalar::XorshiftrScalar;
+pub use seq::Reservoir;
+mod batch;
+mod buffered;
+mod checkpoint;
+mod error;
+mod fill;
+mod io;
+mod kernels;
+mod normal;
+mod permutation;
+mod scalar;
+mod seq;
+
 const DEFAULT_SHL_FIRST: bool = false;
 const DEFAULT_SHL: u32 = 17;
 const DEFAULT_SHR: u32 = 23;
 
+const PAPER_SHL_FIRST: bool = true;
+const PAPER_SHL: u32 = 23;
+const PAPER_SHR: u32 = 17;
+
+/// How many states `try_new` and `try_reseed` draw before giving up.
+pub const SEED_ATTEMPTS: u32 = 8;
+
+/// The order and distances of the two shifts in each lane's update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShiftConfig {
+    /// Whether the left shift is applied before the right shift.
+    pub shl_first: bool,
+    /// The left shift distance.
+    pub shl: u32,
+    /// The right shift distance.
+    pub shr: u32,
+}
+impl ShiftConfig {
+    /// The crate's default configuration, used by [`XorshiftrWide`].
+    /// These shifts last longer in PractRand than the paper's.
+    pub const DEFAULT: Self = Self {
+        shl_first: DEFAULT_SHL_FIRST,
+        shl: DEFAULT_SHL,
+        shr: DEFAULT_SHR,
+    };
+    /// The configuration from the xorshiftR+ paper, used by [`XorshiftrWidePaper`].
+    pub const PAPER: Self = Self {
+        shl_first: PAPER_SHL_FIRST,
+        shl: PAPER_SHL,
+        shr: PAPER_SHR,
+    };
+}
+
 /// A prng designed for autovectorized filling of buffers with random bits,
 /// built from several lanes of individual modified 'xorshiftR+' prngs.
 ///
+/// This is [`XorshiftrWideWith`] using the crate's default shift configuration.
+///
 /// The `LANES` const generic's default of 16 compiles well on x86-64 with either
 /// 128-bit or 256-bit SIMD registers available. Tweaks may squeeze out higher
 /// performance on other architectures, but much lower values produce lower
 /// quality output.
+pub type XorshiftrWide<const LANES: usize = 16> =
+    XorshiftrWideWith<LANES, DEFAULT_SHL_FIRST, DEFAULT_SHL, DEFAULT_SHR>;
+
+/// [`XorshiftrWideWith`] using the shift constants and ordering from the xorshiftR+ paper,
+/// for comparison with the crate's defaults.
+pub type XorshiftrWidePaper<const LANES: usize = 16> =
+    XorshiftrWideWith<LANES, PAPER_SHL_FIRST, PAPER_SHL, PAPER_SHR>;
+
+/// A wide xorshiftR+ prng with a chosen shift configuration.
+///
+/// Each lane's update shifts its first state word right by `SHR` then left by `SHL`,
+/// or left first if `SHL_FIRST` is set, xoring each shift back in. Both shifts must be
+/// in `1..64`, and `LANES` must be non-zero, which is checked at compile time.
+/// Most users want the [`XorshiftrWide`] alias instead.
 #[derive(Clone, Copy, Debug)]
-pub struct XorshiftrWide<const LANES: usize = 16> {
+pub struct XorshiftrWideWith<
+    const LANES: usize,
+    const SHL_FIRST: bool,
+    const SHL: u32,
+    const SHR: u32,
+> {
     state: [[u64; LANES]; 2],
 }
-impl<const LANES: usize> XorshiftrWide<LANES> {
-    /// Reseeds an existing prng instance, seeded with a source of randomness provided by the user.
-    pub fn reseed(&mut self, mut provide_random_u64: impl FnMut() -> u64) {
-        while {
-            for arr in &mut self.state {
-                for ptr in arr {
-                    *ptr = provide_random_u64();
-                }
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>
+{
+    const PARAMETERS_ARE_VALID: () = {
+        assert!(LANES > 0, "LANES must be non-zero");
+        assert!(SHL > 0 && SHL < 64, "SHL must be in 1..64");
+        assert!(SHR > 0 && SHR < 64, "SHR must be in 1..64");
+    };
+    /// The shift configuration of this prng type.
+    pub const SHIFTS: ShiftConfig = ShiftConfig {
+        shl_first: SHL_FIRST,
+        shl: SHL,
+        shr: SHR,
+    };
+    /// Checks a state for lanes with equal words or lanes that share a word.
+    #[allow(clippy::needless_range_loop)]
+    fn check_state(state: &[[u64; LANES]; 2]) -> Result<(), StateError> {
+        let mut need_to_reseed = false;
+        // Check if any lane's two state u64s are the same
+        // In testing on smaller bit widths, abnormally short periods included a state with both the same
+        // In the rare event that we have two of the same u64 in a lane, we should reseed
+        for i in 0..LANES {
+            need_to_reseed |= state[0][i] == state[1][i];
+        }
+        // Check if any two lanes' entire states are the same
+        for left in 0..LANES {
+            for right in (left + 1)..LANES {
+                let top_same = state[0][left] == state[0][right];
+                let bottom_same = state[1][left] == state[1][right];
+                let either_same = top_same | bottom_same;
+                need_to_reseed |= either_same;
+            }
+        }
+        if !need_to_reseed {
+            return Ok(());
+        }
+        // It's astronomically unlikely that a random state fails the checks above,
+        // so only now do we go back with branches to find out which check it was.
+        cold();
+        for lane in 0..LANES {
+            if state[0][lane] == 0 && state[1][lane] == 0 {
+                return Err(StateError::ZeroLane { lane });
             }
-            let mut need_to_reseed = false;
-            // Check if any lane's two state u64s are the same
-            // In testing on smaller bit widths, abnormally short periods included a state with both the same
-            // In the rare event that we have two of the same u64 in a lane, we should reseed
-            for i in 0..LANES {
-                need_to_reseed |= self.state[0][i] == self.state[1][i];
+            if state[0][lane] == state[1][lane] {
+                return Err(StateError::EqualWordsInLane { lane });
             }
-            // Check if any two lanes' entire states are the same
-            for left in 0..LANES {
-                for right in (left + 1)..LANES {
-                    let top_same = self.state[0][left] == self.state[0][right];
-                    let bottom_same = self.state[1][left] == self.state[1][right];
-                    let either_same = top_same | bottom_same;
-                    need_to_reseed |= either_same;
+        }
+        for first in 0..LANES {
+            for second in (first + 1)..LANES {
+                if state[0][first] == state[0][second] || state[1][first] == state[1][second] {
+                    return Err(StateError::DuplicateLanes { first, second });
                 }
             }
-            need_to_reseed
+        }
+        unreachable!()
+    }
+    fn fill_state(state: &mut [[u64; LANES]; 2], provide_random_u64: &mut impl FnMut() -> u64) {
+        for arr in state {
+            for ptr in arr {
+                *ptr = provide_random_u64();
+            }
+        }
+    }
+    /// Reseeds an existing prng instance, seeded with a source of randomness provided by the user.
+    ///
+    /// If the source's output is rejected, this keeps drawing from it until it produces a
+    /// valid state, so a broken source can make this loop forever. See [`Self::try_reseed`].
+    pub fn reseed(&mut self, mut provide_random_u64: impl FnMut() -> u64) {
+        while {
+            Self::fill_state(&mut self.state, &mut provide_random_u64);
+            Self::check_state(&self.state).is_err()
         } {
             // It's astronomically unlikely that we ever need to repeat seeding here,
             // so avoiding branches during the above checks and dropping a cold hint seems reasonable.
             cold();
         }
     }
+    /// Reseeds an existing prng instance, giving up after [`SEED_ATTEMPTS`] rejected states.
+    ///
+    /// On failure, the prng is left unchanged and the reason the last attempt was rejected
+    /// is returned. A working source of randomness fails even once with negligible probability,
+    /// so an error here means the source is broken.
+    pub fn try_reseed(
+        &mut self,
+        mut provide_random_u64: impl FnMut() -> u64,
+    ) -> Result<(), StateError> {
+        let mut candidate = [[0; LANES]; 2];
+        let mut result = Ok(());
+        for _ in 0..SEED_ATTEMPTS {
+            Self::fill_state(&mut candidate, &mut provide_random_u64);
+            result = Self::check_state(&candidate);
+            if result.is_ok() {
+                self.state = candidate;
+                break;
+            }
+        }
+        result
+    }
     /// Creates a new prng instance, seeded with a source of randomness provided by the user.
+    ///
+    /// Like [`Self::reseed`], this never returns if the source can't produce a valid state.
+    /// See [`Self::try_new`].
     pub fn new(provide_random_u64: impl FnMut() -> u64) -> Self {
+        let () = Self::PARAMETERS_ARE_VALID;
         let mut new_state = Self {
             state: [[0; LANES]; 2],
         };
         new_state.reseed(provide_random_u64);
         new_state
     }
+    /// Creates a new prng instance, giving up after [`SEED_ATTEMPTS`] rejected states.
+    pub fn try_new(provide_random_u64: impl FnMut() -> u64) -> Result<Self, StateError> {
+        let () = Self::PARAMETERS_ARE_VALID;
+        let mut new_state = Self {
+            state: [[0; LANES]; 2],
+        };
+        new_state.try_reseed(provide_random_u64)?;
+        Ok(new_state)
+    }
+    /// Creates a prng instance from a raw state, such as one saved earlier with [`Self::state`].
+    ///
+    /// States that `reseed` would reject are refused: lanes that are all zero, lanes with
+    /// both words equal, and lanes sharing a word with another lane.
+    pub fn try_from_state(state: [[u64; LANES]; 2]) -> Result<Self, StateError> {
+        let () = Self::PARAMETERS_ARE_VALID;
+        Self::check_state(&state)?;
+        Ok(Self { state })
+    }
+    /// Returns the raw state, which can be restored later with [`Self::try_from_state`].
+    ///
+    /// `state()[0][i]` and `state()[1][i]` are the two words of lane `i`.
+    pub fn state(&self) -> [[u64; LANES]; 2] {
+        self.state
+    }
+    /// Creates a new prng instance deterministically from a 64-bit seed.
+    ///
+    /// The state is drawn, in the order `reseed` fills it, from a SplitMix64 generator
+    /// whose state starts at `seed`. This expansion is fixed, so a given seed produces
+    /// the same output in every version of the crate.
+    pub fn from_seed_u64(seed: u64) -> Self {
+        let mut expander = SplitMix64::new(seed);
+        Self::new(|| expander.next_u64())
+    }
+    /// Creates a new prng instance deterministically from a 128-bit seed.
+    ///
+    /// The low and high halves of `seed` start two SplitMix64 counters, which both step by
+    /// the usual golden-ratio increment. Each word drawn is `mix(low ^ mix(high))`, where
+    /// `mix` is the SplitMix64 output function. As with [`Self::from_seed_u64`], this
+    /// expansion is fixed across versions of the crate.
+    pub fn from_seed_u128(seed: u128) -> Self {
+        let mut expander = SplitMix128::new(seed);
+        Self::new(|| expander.next_u64())
+    }
+    /// Advances every lane by one step, writing each lane's output to `block`.
+    /// This is the per-lane recurrence shared by everything that generates output.
+    #[inline(always)]
+    #[allow(clippy::needless_range_loop)]
+    fn next_block(&mut self, block: &mut [u64; LANES]) {
+        for i in 0..LANES {
+            let mut x = self.state[0][i];
+            let y = self.state[1][i];
+            self.state[0][i] = y;
+            if const { SHL_FIRST } {
+                x ^= x << SHL;
+                x ^= x >> SHR;
+            } else {
+                x ^= x >> SHR;
+                x ^= x << SHL;
+            }
+            self.state[1][i] = x.wrapping_add(y);
+            block[i] = x;
+        }
+    }
     /// Core function to fill a buffer with random data.
-    /// This function is generic over the shift parameters to allow for
+    /// The shift parameters come from the type, to allow for
     /// different configurations of the algorithm.
     #[inline(never)]
-    fn fill_core<const SHL_FIRST: bool, const SHL_BY: u32, const SHR_BY: u32>(
-        &mut self,
-        buffer: &mut [u64],
-    ) {
-        let mut exact_width_chunks = buffer.chunks_exact_mut(LANES);
-        for chunk in exact_width_chunks.by_ref() {
-            let chunk_as_array: &mut [u64; LANES] = unsafe { chunk.try_into().unwrap_unchecked() };
-            for i in 0..LANES {
-                let mut x = self.state[0][i];
-                let y = self.state[1][i];
-                self.state[0][i] = y;
-                if const { SHL_FIRST } {
-                    x ^= x << SHL_BY;
-                    x ^= x >> SHR_BY;
-                } else {
-                    x ^= x >> SHR_BY;
-                    x ^= x << SHL_BY;
-                }
-                self.state[1][i] = x.wrapping_add(y);
-                chunk_as_array[i] = x;
-            }
-        }
-        let tail = exact_width_chunks.into_remainder();
+    fn fill_core(&mut self, buffer: &mut [u64]) {
+        self.fill_core_with_kernel(Kernel::detect(), buffer);
+    }
+    #[inline(always)]
+    fn fill_core_with_kernel(&mut self, kernel: Kernel, buffer: &mut [u64]) {
+        let whole_blocks_len = buffer.len() / LANES * LANES;
+        let (whole_blocks, tail) = buffer.split_at_mut(whole_blocks_len);
+        self.fill_blocks(kernel, whole_blocks);
         if !tail.is_empty() {
             cold();
             let mut temporary_buffer = [0u64; LANES];
-            self.fill_core::<SHL_FIRST, SHL_BY, SHR_BY>(&mut temporary_buffer[..]);
+            self.next_block(&mut temporary_buffer);
             let qty_to_copy = tail.len();
             let random_bits_to_copy = &temporary_buffer[..qty_to_copy];
             tail.copy_from_slice(random_bits_to_copy);
         }
     }
+    /// Core function to fill a byte buffer with random data.
+    /// Bytes are the little-endian representation of the words `fill_core` would produce
+    /// for a buffer of `buffer.len().div_ceil(8)` u64s, regardless of the buffer's alignment.
+    fn fill_bytes_core(&mut self, buffer: &mut [u8]) {
+        const BLOCKS_PER_COPY: usize = 8;
+        let mut remaining = buffer;
+        // If the buffer happens to be aligned for u64, whole blocks can be generated in place
+        if remaining.as_ptr().cast::<u64>().is_aligned() {
+            let in_place_len = remaining.len() / (LANES * 8) * (LANES * 8);
+            let (in_place, rest) = remaining.split_at_mut(in_place_len);
+            // SAFETY: the pointer is aligned for u64, the length is a multiple of 8 bytes,
+            // and every bit pattern is a valid u64.
+            let words: &mut [u64] = unsafe {
+                core::slice::from_raw_parts_mut(in_place.as_mut_ptr().cast(), in_place_len / 8)
+            };
+            self.fill_core(words);
+            for word in words {
+                *word = word.to_le();
+            }
+            remaining = rest;
+        }
+        // Anything left over, including all of a misaligned buffer, goes through a temporary buffer
+        let mut temporary_buffer = [[0u64; LANES]; BLOCKS_PER_COPY];
+        let temporary_buffer = temporary_buffer.as_flattened_mut();
+        for chunk in remaining.chunks_mut(temporary_buffer.len() * 8) {
+            let words = &mut temporary_buffer[..chunk.len().div_ceil(8)];
+            self.fill_core(words);
+            let mut byte_chunks = chunk.chunks_exact_mut(8);
+            for (bytes, word) in byte_chunks.by_ref().zip(words.iter()) {
+                bytes.copy_from_slice(&word.to_le_bytes());
+            }
+            let tail = byte_chunks.into_remainder();
+            if !tail.is_empty() {
+                let last_word = words[words.len() - 1];
+                tail.copy_from_slice(&last_word.to_le_bytes()[..tail.len()]);
+            }
+        }
+    }
+    /// Advances every lane by `steps` steps, skipping `steps * LANES` u64s of output.
+    ///
+    /// This takes time proportional to `steps`, as it simply runs the lanes forward.
+    /// Xorshift generators can usually jump ahead in logarithmic time, because their
+    /// update is a linear map over GF(2) that can be raised to a power. Here, though,
+    /// each lane's sum is fed back into its state, and the carries of that wrapping add
+    /// make the update non-linear, so there is no matrix or characteristic polynomial
+    /// to exponentiate, and no known way to skip ahead faster than stepping.
+    ///
+    /// For non-overlapping substreams across many workers, seed each worker's prng
+    /// independently instead; with 2^128 possible states per lane, overlap between
+    /// independently seeded streams of any practical length is vanishingly unlikely.
+    pub fn jump_by(&mut self, steps: u64) {
+        let mut discarded = [0u64; LANES];
+        for _ in 0..steps {
+            self.next_block(&mut discarded);
+        }
+    }
     /// Fills a slice of u64 with random data.
     #[inline]
     pub fn fill_u64_buffer(&mut self, destination_buffer: &mut [u64]) {
-        self.fill_core::<DEFAULT_SHL_FIRST, DEFAULT_SHL, DEFAULT_SHR>(destination_buffer);
+        self.fill_core(destination_buffer);
+    }
+    /// Fills a slice of bytes with random data.
+    ///
+    /// Each u64 of output is written in little-endian order, so the bytes match
+    /// `fill_u64_buffer` on every target, and don't depend on how the slice is aligned.
+    #[inline]
+    pub fn fill_bytes(&mut self, destination_buffer: &mut [u8]) {
+        self.fill_bytes_core(destination_buffer);
     }
 }
 
@@ -101,4 +358,47 @@ pub(crate) mod private_utils {
     #[inline(always)]
     #[cold]
     pub(crate) fn cold() {}
+
+    const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    /// The output function of SplitMix64.
+    #[inline(always)]
+    pub(crate) fn splitmix64_mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Sebastiano Vigna's SplitMix64, used to expand small seeds into full states.
+    pub(crate) struct SplitMix64 {
+        state: u64,
+    }
+    impl SplitMix64 {
+        pub(crate) fn new(seed: u64) -> Self {
+            Self { state: seed }
+        }
+        pub(crate) fn next_u64(&mut self) -> u64 {
+            self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+            splitmix64_mix(self.state)
+        }
+    }
+
+    /// Two SplitMix64 counters combined, so every bit of a 128-bit seed affects every word.
+    pub(crate) struct SplitMix128 {
+        low: u64,
+        high: u64,
+    }
+    impl SplitMix128 {
+        pub(crate) fn new(seed: u128) -> Self {
+            Self {
+                low: seed as u64,
+                high: (seed >> 64) as u64,
+            }
+        }
+        pub(crate) fn next_u64(&mut self) -> u64 {
+            self.low = self.low.wrapping_add(GOLDEN_GAMMA);
+            self.high = self.high.wrapping_add(GOLDEN_GAMMA);
+            splitmix64_mix(self.low ^ splitmix64_mix(self.high))
+        }
+    }
 }
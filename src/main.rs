@@ -0,0 +1,202 @@
+//! Streams raw xorshiftr-wide output to stdout, for statistical test suites such as
+//! PractRand, TestU01 and dieharder.
+
+use std::io::{self, Write};
+use std::process::ExitCode;
+use xorshiftr_wide::{XorshiftrWide, XorshiftrWidePaper, XorshiftrWideWith, write_random};
+
+const USAGE: &str = "\
+Usage: xorshiftr-wide [OPTIONS]
+
+Writes raw generator output to stdout, endlessly unless --bytes is given.
+
+Options:
+  --seed <N>          Seed for from_seed_u64, in decimal or 0x-prefixed hex [default: 0]
+  --lanes <N>         Number of lanes: 4, 8, 16, 32 or 64 [default: 16]
+  --preset <NAME>     Shift configuration: default or paper [default: default]
+  --bytes <N>         Stop after N bytes; K, M, G and T suffixes multiply by powers of 1024
+  --view <NAME>       raw: every u64 in little-endian order
+                      reversed: every u64 with its bits reversed, in little-endian order
+                      low32: the low 32 bits of every u64, in little-endian order
+                      [default: raw]
+  -h, --help          Print this message
+";
+
+/// The lane counts compiled into the binary.
+const LANE_COUNTS: [usize; 5] = [4, 8, 16, 32, 64];
+
+#[derive(Clone, Copy, Debug)]
+enum Preset {
+    Default,
+    Paper,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum View {
+    Raw,
+    Reversed,
+    Low32,
+}
+
+#[derive(Debug)]
+struct Options {
+    seed: u64,
+    lanes: usize,
+    preset: Preset,
+    bytes: Option<u64>,
+    view: View,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprint!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let mut stdout = io::stdout().lock();
+    match dispatch(&options, &mut stdout).and_then(|()| stdout.flush()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // The reader has seen all it wants, which is the usual way for a test suite to stop
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parses the command line, returning `None` if help was asked for.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        seed: 0,
+        lanes: 16,
+        preset: Preset::Default,
+        bytes: None,
+        view: View::Raw,
+    };
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(None);
+        }
+        // Values can follow either an `=` or a space
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), value.to_string()),
+            None => {
+                let value = args.next().ok_or_else(|| format!("{arg} needs a value"))?;
+                (arg, value)
+            }
+        };
+        match name.as_str() {
+            "--seed" => options.seed = parse_seed(&value)?,
+            "--lanes" => {
+                options.lanes = value
+                    .parse()
+                    .ok()
+                    .filter(|lanes| LANE_COUNTS.contains(lanes))
+                    .ok_or_else(|| format!("unsupported lane count {value}"))?;
+            }
+            "--preset" => {
+                options.preset = match value.as_str() {
+                    "default" => Preset::Default,
+                    "paper" => Preset::Paper,
+                    _ => return Err(format!("unknown preset {value}")),
+                };
+            }
+            "--bytes" => options.bytes = Some(parse_bytes(&value)?),
+            "--view" => {
+                options.view = match value.as_str() {
+                    "raw" => View::Raw,
+                    "reversed" => View::Reversed,
+                    "low32" => View::Low32,
+                    _ => return Err(format!("unknown view {value}")),
+                };
+            }
+            _ => return Err(format!("unknown option {name}")),
+        }
+    }
+    Ok(Some(options))
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .map_err(|_| format!("invalid seed {value}"))
+}
+
+fn parse_bytes(value: &str) -> Result<u64, String> {
+    let (digits, shift) = match value.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&value[..i], 10),
+        Some((i, 'M' | 'm')) => (&value[..i], 20),
+        Some((i, 'G' | 'g')) => (&value[..i], 30),
+        Some((i, 'T' | 't')) => (&value[..i], 40),
+        _ => (value, 0),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(1 << shift))
+        .ok_or_else(|| format!("invalid byte count {value}"))
+}
+
+/// Runs the prng variant the options ask for.
+fn dispatch(options: &Options, out: &mut impl Write) -> io::Result<()> {
+    macro_rules! with_lanes {
+        ($prng:ident) => {
+            match options.lanes {
+                4 => stream($prng::<4>::from_seed_u64(options.seed), options, out),
+                8 => stream($prng::<8>::from_seed_u64(options.seed), options, out),
+                16 => stream($prng::<16>::from_seed_u64(options.seed), options, out),
+                32 => stream($prng::<32>::from_seed_u64(options.seed), options, out),
+                64 => stream($prng::<64>::from_seed_u64(options.seed), options, out),
+                _ => unreachable!("lane counts are checked while parsing"),
+            }
+        };
+    }
+    match options.preset {
+        Preset::Default => with_lanes!(XorshiftrWide),
+        Preset::Paper => with_lanes!(XorshiftrWidePaper),
+    }
+}
+
+/// Writes output in the chosen view until the byte limit, if any, is reached.
+/// The raw view goes through `write_random`, and the others through their own loop.
+fn stream<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+    mut rng: XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+    options: &Options,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    // A multiple of every compiled lane count, so no output is skipped between writes
+    const WORDS_PER_WRITE: usize = 1 << 13;
+    let convert: fn(u64, &mut Vec<u8>) = match options.view {
+        // u64::MAX bytes takes long enough to write that it might as well be endless
+        View::Raw => return write_random(&mut rng, out, options.bytes.unwrap_or(u64::MAX)),
+        View::Reversed => |word, bytes| bytes.extend_from_slice(&word.reverse_bits().to_le_bytes()),
+        View::Low32 => |word, bytes| bytes.extend_from_slice(&(word as u32).to_le_bytes()),
+    };
+    let mut words = vec![0u64; WORDS_PER_WRITE];
+    let mut bytes = Vec::with_capacity(WORDS_PER_WRITE * 8);
+    let mut remaining = options.bytes;
+    while remaining != Some(0) {
+        rng.fill_u64_buffer(&mut words);
+        bytes.clear();
+        for &word in &words {
+            convert(word, &mut bytes);
+        }
+        let len = remaining.map_or(bytes.len(), |remaining| {
+            remaining.min(bytes.len() as u64) as usize
+        });
+        out.write_all(&bytes[..len])?;
+        if let Some(remaining) = &mut remaining {
+            *remaining -= len as u64;
+        }
+    }
+    Ok(())
+}
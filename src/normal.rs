@@ -0,0 +1,80 @@
+use crate::*;
+use core::f32::consts::TAU as TAU_F32;
+use core::f64::consts::TAU;
+use fill::{F32_SCALE, F64_SCALE};
+
+// The Box–Muller transform turns two uniform values into two independent standard normals,
+// with no rejection, so every word of a block is used the same way and the loop stays
+// branch-free. `u1` must be in (0, 1] so its logarithm is finite.
+#[inline(always)]
+fn box_muller_f64(first: u64, second: u64) -> [f64; 2] {
+    let u1 = ((first >> 11) + 1) as f64 * F64_SCALE;
+    let u2 = (second >> 11) as f64 * F64_SCALE;
+    let radius = (-2.0 * u1.ln()).sqrt();
+    let (sin, cos) = (TAU * u2).sin_cos();
+    [radius * cos, radius * sin]
+}
+
+#[inline(always)]
+fn box_muller_f32(word: u64) -> [f32; 2] {
+    let [low, high] = fill::split_u32(word);
+    let u1 = ((high >> 8) + 1) as f32 * F32_SCALE;
+    let u2 = (low >> 8) as f32 * F32_SCALE;
+    let radius = (-2.0 * u1.ln()).sqrt();
+    let (sin, cos) = (TAU_F32 * u2).sin_cos();
+    [radius * cos, radius * sin]
+}
+
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>
+{
+    /// Fills a slice of f64 with normally distributed random values.
+    ///
+    /// Values come in pairs from the Box–Muller transform of two consecutive u64s of output,
+    /// using 53 bits of each, so the most extreme value possible is about 8.6 standard
+    /// deviations from the mean.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mean` isn't finite, or `std_dev` isn't finite and non-negative.
+    pub fn fill_normal_f64(&mut self, destination_buffer: &mut [f64], mean: f64, std_dev: f64) {
+        assert!(mean.is_finite(), "mean must be finite");
+        assert!(
+            std_dev.is_finite() && std_dev >= 0.0,
+            "std_dev must be finite and non-negative"
+        );
+        self.fill_f64_bits(destination_buffer);
+        let (pairs, tail) = destination_buffer.as_chunks_mut::<2>();
+        for pair in pairs {
+            let [first, second] = box_muller_f64(pair[0].to_bits(), pair[1].to_bits());
+            *pair = [mean + std_dev * first, mean + std_dev * second];
+        }
+        // An odd value out takes its partner from the start of one more block
+        if let [value] = tail {
+            cold();
+            let mut block = [0u64; LANES];
+            self.next_block(&mut block);
+            let [first, _] = box_muller_f64(value.to_bits(), block[0]);
+            *value = mean + std_dev * first;
+        }
+    }
+    /// Fills a slice of f32 with normally distributed random values.
+    ///
+    /// Each u64 of output makes a pair of values from the Box–Muller transform of its two
+    /// halves, using 24 bits of each, so the most extreme value possible is about 5.8 standard
+    /// deviations from the mean.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mean` isn't finite, or `std_dev` isn't finite and non-negative.
+    pub fn fill_normal_f32(&mut self, destination_buffer: &mut [f32], mean: f32, std_dev: f32) {
+        assert!(mean.is_finite(), "mean must be finite");
+        assert!(
+            std_dev.is_finite() && std_dev >= 0.0,
+            "std_dev must be finite and non-negative"
+        );
+        self.fill_mapped(destination_buffer, |word| {
+            box_muller_f32(word).map(|value| mean + std_dev * value)
+        });
+    }
+}
@@ -0,0 +1,74 @@
+use crate::*;
+
+/// A random permutation of `0..len`, computed one index at a time without storing it.
+///
+/// Indices are encrypted with a small keyed Feistel network over the smallest even number of
+/// bits that can hold them, and encrypted again until the result is back in range
+/// ("cycle-walking"). That domain is less than four times the length, so each index takes a
+/// few rounds of the network on average. The keys are drawn from a prng when it's created,
+/// which makes the permutation random, although not uniformly so over all `len!` orders.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RandomPermutation {
+    len: u64,
+    half_bits: u32,
+    keys: [u64; Self::ROUNDS],
+}
+impl RandomPermutation {
+    const ROUNDS: usize = 6;
+    /// Creates a random permutation of `0..len`, keyed from `rng`.
+    pub fn new<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        len: u64,
+    ) -> Self {
+        let mut keys = [0; Self::ROUNDS];
+        rng.fill_u64_buffer(&mut keys);
+        let bits = u64::BITS - len.saturating_sub(1).leading_zeros();
+        Self {
+            len,
+            half_bits: bits.div_ceil(2).max(1),
+            keys,
+        }
+    }
+    /// The number of indices being permuted.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+    /// Whether there are no indices to permute.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Where `index` goes in the permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` isn't below `len`.
+    pub fn get(&self, index: u64) -> u64 {
+        assert!(
+            index < self.len,
+            "index {index} is out of range for a permutation of length {}",
+            self.len
+        );
+        let mut value = index;
+        loop {
+            value = self.encrypt(value);
+            if value < self.len {
+                return value;
+            }
+        }
+    }
+    /// Iterates over the whole permutation, from where 0 goes to where `len - 1` goes.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.len).map(|index| self.get(index))
+    }
+    /// One pass of the Feistel network, which permutes `0..2^(2 * half_bits)`.
+    #[inline(always)]
+    fn encrypt(&self, value: u64) -> u64 {
+        let mask = u64::MAX >> (u64::BITS - self.half_bits);
+        let (mut left, mut right) = (value >> self.half_bits, value & mask);
+        for &key in &self.keys {
+            let mixed = splitmix64_mix(right ^ key) >> (u64::BITS - self.half_bits);
+            (left, right) = (right, left ^ mixed);
+        }
+        left << self.half_bits | right
+    }
+}
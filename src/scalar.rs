@@ -0,0 +1,55 @@
+use crate::*;
+
+/// A plain single-lane xorshiftR+ prng, following exactly the recurrence each lane of
+/// [`XorshiftrWideWith`] runs.
+///
+/// This is far slower than the wide prng, and isn't meant for generating bulk output.
+/// It is a reference for auditing changes to the wide prng and for porting the algorithm:
+/// lane `i` of a wide prng produces the same sequence as
+/// `XorshiftrScalar::from_lane(&wide, i)`.
+#[derive(Clone, Copy, Debug)]
+pub struct XorshiftrScalar<
+    const SHL_FIRST: bool = DEFAULT_SHL_FIRST,
+    const SHL: u32 = DEFAULT_SHL,
+    const SHR: u32 = DEFAULT_SHR,
+> {
+    state: [u64; 2],
+}
+impl<const SHL_FIRST: bool, const SHL: u32, const SHR: u32> XorshiftrScalar<SHL_FIRST, SHL, SHR> {
+    /// Creates a prng from the two words of a lane's state.
+    ///
+    /// Unlike the wide prng, no checks are applied, so an all-zero state is accepted
+    /// and produces nothing but zeros.
+    pub fn from_state(state: [u64; 2]) -> Self {
+        let () = XorshiftrWideWith::<1, SHL_FIRST, SHL, SHR>::PARAMETERS_ARE_VALID;
+        Self { state }
+    }
+    /// Creates a prng that continues the sequence of one lane of a wide prng.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lane` is not less than `LANES`.
+    pub fn from_lane<const LANES: usize>(
+        wide: &XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        lane: usize,
+    ) -> Self {
+        Self::from_state([wide.state[0][lane], wide.state[1][lane]])
+    }
+    /// Returns the two words of the state.
+    pub fn state(&self) -> [u64; 2] {
+        self.state
+    }
+    /// Returns the next u64 of output.
+    pub fn next_u64(&mut self) -> u64 {
+        let [mut x, y] = self.state;
+        if SHL_FIRST {
+            x ^= x << SHL;
+            x ^= x >> SHR;
+        } else {
+            x ^= x >> SHR;
+            x ^= x << SHL;
+        }
+        self.state = [y, x.wrapping_add(y)];
+        x
+    }
+}
@@ -0,0 +1,135 @@
+use crate::batch::WordBatch;
+use crate::*;
+use std::collections::HashMap;
+
+impl<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>
+    XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>
+{
+    /// Shuffles a slice into a uniformly random order, using the Fisher–Yates shuffle.
+    ///
+    /// This gives the same result as `partial_shuffle(values, values.len())`, and the result for
+    /// a given state will stay the same across versions of this crate.
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        self.partial_shuffle(values, values.len());
+    }
+    /// Moves a uniformly random selection of `amount` elements, in a uniformly random order,
+    /// to the front of a slice, and returns it along with the rest of the slice. An `amount`
+    /// larger than the slice shuffles all of it.
+    ///
+    /// Working forwards from the start of the slice, each position is swapped with a position
+    /// at or after it, chosen from one u64 of output by Lemire's method, with rare rejections
+    /// taking another. Output is drawn in batches, and the result for a given state will stay
+    /// the same across versions of this crate.
+    pub fn partial_shuffle<'a, T>(
+        &mut self,
+        values: &'a mut [T],
+        amount: usize,
+    ) -> (&'a mut [T], &'a mut [T]) {
+        let amount = amount.min(values.len());
+        // The last position has nothing after it to swap with
+        let swaps = amount.min(values.len().saturating_sub(1));
+        let mut words = WordBatch::new(self);
+        for position in 0..swaps {
+            let remaining = (values.len() - position) as u64;
+            let other = position + words.below(remaining) as usize;
+            values.swap(position, other);
+        }
+        values.split_at_mut(amount)
+    }
+    /// Chooses `amount` distinct indices from `0..length` uniformly at random, in a uniformly
+    /// random order.
+    ///
+    /// Small samples use Floyd's algorithm, taking time and memory proportional to `amount`.
+    /// Samples of more than a quarter of the indices partially shuffle all of them instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is greater than `length`.
+    pub fn sample_indices(&mut self, length: usize, amount: usize) -> Vec<usize> {
+        assert!(
+            amount <= length,
+            "cannot sample {amount} indices from {length}"
+        );
+        if amount > length / 4 {
+            let mut indices: Vec<usize> = (0..length).collect();
+            self.partial_shuffle(&mut indices, amount);
+            indices.truncate(amount);
+            return indices;
+        }
+        // Floyd's algorithm, which adds either a new random index or the largest one so far.
+        // Where the random index was already chosen, the largest index takes its place, so the
+        // order is random too.
+        let mut indices = Vec::with_capacity(amount);
+        let mut positions = HashMap::with_capacity(amount);
+        let mut words = WordBatch::new(self);
+        for largest in length - amount..length {
+            let index = words.below(largest as u64 + 1) as usize;
+            if let Some(&position) = positions.get(&index) {
+                indices[position] = largest;
+                positions.insert(largest, position);
+            }
+            positions.insert(index, indices.len());
+            indices.push(index);
+        }
+        indices
+    }
+}
+
+/// A uniform random sample of up to `capacity` items from a stream of any length,
+/// kept with Vitter's Algorithm R.
+#[derive(Clone, Debug)]
+pub struct Reservoir<T> {
+    capacity: usize,
+    items: Vec<T>,
+    seen: u64,
+}
+impl<T> Reservoir<T> {
+    /// Creates an empty reservoir that keeps up to `capacity` items.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            items: Vec::with_capacity(capacity),
+            seen: 0,
+        }
+    }
+    /// Offers every item from `items` to the reservoir.
+    ///
+    /// Once the reservoir is full, the `n`th item seen replaces a random kept item with
+    /// probability `capacity / n`. Output is drawn in batches, and whatever is left of the last
+    /// batch is discarded, so offering items in one call takes less output than in many.
+    pub fn extend<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+        &mut self,
+        rng: &mut XorshiftrWideWith<LANES, SHL_FIRST, SHL, SHR>,
+        items: impl IntoIterator<Item = T>,
+    ) {
+        let mut items = items.into_iter();
+        // Until the reservoir is full, every item is kept
+        while self.items.len() < self.capacity {
+            let Some(item) = items.next() else {
+                return;
+            };
+            self.items.push(item);
+            self.seen += 1;
+        }
+        let mut words = WordBatch::new(rng);
+        for item in items {
+            self.seen += 1;
+            let position = words.below(self.seen) as usize;
+            if position < self.capacity {
+                self.items[position] = item;
+            }
+        }
+    }
+    /// The number of items offered so far.
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+    /// The items currently kept, which are all of them until more than `capacity` have been seen.
+    pub fn sample(&self) -> &[T] {
+        &self.items
+    }
+    /// Consumes the reservoir, returning the items it kept.
+    pub fn into_sample(self) -> Vec<T> {
+        self.items
+    }
+}
@@ -0,0 +1,49 @@
+use xorshiftr_wide::{BufferedXorshiftrWide, XorshiftrWide};
+
+fn words(seed: u64, len: usize) -> Vec<u64> {
+    let mut words = vec![0; len];
+    XorshiftrWide::<16>::from_seed_u64(seed).fill_u64_buffer(&mut words);
+    words
+}
+
+#[test]
+fn single_values_continue_the_stream() {
+    let expected = words(1, 600);
+    let mut rng = BufferedXorshiftrWide::new(XorshiftrWide::<16>::from_seed_u64(1));
+    let mut values = vec![rng.next_u64(), rng.next_u32() as u64];
+    let mut filled = [0; 21];
+    rng.fill_u64_buffer(&mut filled);
+    values.extend(filled);
+    values.extend(rng.by_ref().take(50));
+    // Leftovers always finish off a block
+    assert_eq!(rng.leftover_len() % 16, 16 - 73 % 16);
+    // Enough single values to need several refills
+    values.extend((0..527).map(|_| rng.next_u64()));
+    assert_eq!(values[0], expected[0]);
+    assert_eq!(values[1], expected[1] as u32 as u64);
+    assert_eq!(values[2..], expected[2..]);
+}
+
+#[test]
+fn output_does_not_depend_on_chunking() {
+    let expected = words(2, 200);
+    let patterns: [&[usize]; 6] = [
+        &[10, 6],
+        &[15, 1, 16, 17],
+        &[1; 40],
+        &[16, 16, 16],
+        &[33, 0, 31, 2, 47, 14],
+        &[5, 48, 3, 100],
+    ];
+    for pattern in patterns {
+        let mut rng = BufferedXorshiftrWide::new(XorshiftrWide::<16>::from_seed_u64(2));
+        let mut filled = Vec::new();
+        for &len in pattern {
+            let mut chunk = vec![0; len];
+            rng.fill_u64_buffer(&mut chunk);
+            filled.extend(chunk);
+        }
+        assert_eq!(filled, expected[..filled.len()], "{pattern:?}");
+        assert_eq!(rng.leftover_len() % 16, (16 - filled.len() % 16) % 16);
+    }
+}
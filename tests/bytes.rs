@@ -0,0 +1,33 @@
+use xorshiftr_wide::XorshiftrWide;
+
+#[test]
+fn fill_bytes_matches_little_endian_words_at_any_alignment() {
+    const BLOCK_BYTES: usize = 16 * 8;
+    const COPY_BYTES: usize = 8 * BLOCK_BYTES;
+    let mut buffer = vec![0u8; 3 * COPY_BYTES + 16];
+    // Offsets count from the first 8-byte aligned byte, so offset 0 takes the in-place path
+    let aligned = buffer.as_ptr().align_offset(8);
+    let lens = [
+        0,
+        1,
+        7,
+        8,
+        BLOCK_BYTES - 1,
+        BLOCK_BYTES,
+        BLOCK_BYTES + 1,
+        COPY_BYTES - 1,
+        COPY_BYTES,
+        COPY_BYTES + 1,
+        2 * COPY_BYTES + BLOCK_BYTES + 3,
+    ];
+    for offset in 0..8 {
+        for len in lens {
+            let mut words = vec![0u64; len.div_ceil(8)];
+            XorshiftrWide::<16>::from_seed_u64(1).fill_u64_buffer(&mut words);
+            let expected: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+            let bytes = &mut buffer[aligned + offset..][..len];
+            XorshiftrWide::<16>::from_seed_u64(1).fill_bytes(bytes);
+            assert_eq!(bytes, &expected[..len], "offset {offset}, length {len}");
+        }
+    }
+}
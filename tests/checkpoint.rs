@@ -0,0 +1,92 @@
+use xorshiftr_wide::{CheckpointError, StateError, XorshiftrWide};
+
+#[test]
+fn checkpoint_format_is_stable() {
+    let checkpoint = XorshiftrWide::<1>::from_seed_u64(0).to_checkpoint();
+    let mut expected = Vec::new();
+    expected.extend_from_slice(b"XSHRWIDE");
+    expected.extend_from_slice(&1u32.to_le_bytes());
+    expected.extend_from_slice(&1u32.to_le_bytes());
+    expected.extend_from_slice(&[0, 17, 23, 0]);
+    expected.extend_from_slice(&0xe220a8397b1dcdafu64.to_le_bytes());
+    expected.extend_from_slice(&0x6e789e6aa1b965f4u64.to_le_bytes());
+    expected.extend_from_slice(&0xf64f510cu32.to_le_bytes());
+    assert_eq!(checkpoint, expected);
+}
+
+#[test]
+fn checkpoint_round_trips() {
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(11);
+    rng.fill_u64_buffer(&mut [0; 100]);
+    let mut restored = XorshiftrWide::<16>::from_checkpoint(&rng.to_checkpoint()).unwrap();
+    let (mut expected, mut actual) = ([0; 64], [0; 64]);
+    rng.fill_u64_buffer(&mut expected);
+    restored.fill_u64_buffer(&mut actual);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn checkpoint_rejects_mismatches_and_corruption() {
+    let checkpoint = XorshiftrWide::<8>::from_seed_u64(5).to_checkpoint();
+    assert_eq!(
+        XorshiftrWide::<16>::from_checkpoint(&checkpoint).unwrap_err(),
+        CheckpointError::LaneMismatch {
+            expected: 16,
+            found: 8
+        }
+    );
+    let mut corrupted = checkpoint.clone();
+    corrupted[40] ^= 1;
+    assert_eq!(
+        XorshiftrWide::<8>::from_checkpoint(&corrupted).unwrap_err(),
+        CheckpointError::ChecksumMismatch
+    );
+    let mut bad_magic = checkpoint.clone();
+    bad_magic[0] = b'Y';
+    assert_eq!(
+        XorshiftrWide::<8>::from_checkpoint(&bad_magic).unwrap_err(),
+        CheckpointError::BadMagic
+    );
+    assert_eq!(
+        XorshiftrWide::<8>::from_checkpoint(&checkpoint[..10]).unwrap_err(),
+        CheckpointError::WrongLength
+    );
+}
+
+#[test]
+fn checkpoint_rejects_invalid_states() {
+    let mut checkpoint = XorshiftrWide::<1>::from_seed_u64(0).to_checkpoint();
+    checkpoint[20..36].fill(0);
+    let checksum_start = checkpoint.len() - 4;
+    let checksum = crc32(&checkpoint[..checksum_start]);
+    checkpoint[checksum_start..].copy_from_slice(&checksum.to_le_bytes());
+    assert_eq!(
+        XorshiftrWide::<1>::from_checkpoint(&checkpoint).unwrap_err(),
+        CheckpointError::InvalidState(StateError::ZeroLane { lane: 0 })
+    );
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[test]
+fn checkpoint_rejects_other_shift_configurations() {
+    use xorshiftr_wide::{ShiftConfig, XorshiftrWidePaper};
+    let checkpoint = XorshiftrWidePaper::<16>::from_seed_u64(5).to_checkpoint();
+    assert_eq!(
+        XorshiftrWide::<16>::from_checkpoint(&checkpoint).unwrap_err(),
+        CheckpointError::ShiftMismatch {
+            expected: ShiftConfig::DEFAULT,
+            found: ShiftConfig::PAPER
+        }
+    );
+    assert!(XorshiftrWidePaper::<16>::from_checkpoint(&checkpoint).is_ok());
+}
@@ -0,0 +1,47 @@
+use std::process::Command;
+use xorshiftr_wide::{XorshiftrWide, XorshiftrWidePaper};
+
+fn run(args: &[&str]) -> Vec<u8> {
+    let output = Command::new(env!("CARGO_BIN_EXE_xorshiftr-wide"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{args:?} failed");
+    output.stdout
+}
+
+#[test]
+fn binary_output_matches_the_library() {
+    let mut expected = vec![0; 100_003];
+    XorshiftrWide::<16>::from_seed_u64(42).fill_bytes(&mut expected);
+    assert_eq!(run(&["--seed", "42", "--bytes", "100003"]), expected);
+
+    let mut words = vec![0u64; 2048];
+    XorshiftrWidePaper::<8>::from_seed_u64(0x2a).fill_u64_buffer(&mut words);
+    let reversed: Vec<u8> = words
+        .iter()
+        .flat_map(|word| word.reverse_bits().to_le_bytes())
+        .collect();
+    let low32: Vec<u8> = words
+        .iter()
+        .flat_map(|&word| (word as u32).to_le_bytes())
+        .collect();
+    let paper = ["--seed=0x2a", "--lanes=8", "--preset=paper", "--bytes=8K"];
+    assert_eq!(run(&[&paper[..], &["--view", "low32"]].concat()), low32);
+    assert_eq!(
+        run(&[&paper[..], &["--view", "reversed"]].concat())[..],
+        reversed[..8192]
+    );
+}
+
+#[test]
+fn binary_rejects_bad_options() {
+    for args in [&["--lanes", "5"][..], &["--view", "sideways"], &["--bytes"]] {
+        let status = Command::new(env!("CARGO_BIN_EXE_xorshiftr-wide"))
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert_eq!(status.code(), Some(2), "{args:?}");
+    }
+}
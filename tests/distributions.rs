@@ -0,0 +1,438 @@
+use xorshiftr_wide::XorshiftrWide;
+use xorshiftr_wide::distributions::{
+    AliasTable, Bernoulli, Beta, Binomial, ChiSquared, Exponential, Gamma, Geometric,
+    ParameterError, Poisson, WeightError,
+};
+
+const SAMPLES: usize = 100_003;
+
+/// Runs a Kolmogorov–Smirnov test of samples against a CDF, at a significance level of 0.001.
+fn assert_matches_cdf(mut samples: Vec<f64>, cdf: impl Fn(f64) -> f64, name: &str) {
+    let non_finite = samples.iter().filter(|x| !x.is_finite()).count();
+    assert_eq!(non_finite, 0, "{name} produced non-finite samples");
+    samples.sort_by(f64::total_cmp);
+    let n = samples.len() as f64;
+    let statistic = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let expected = cdf(x);
+            (expected - i as f64 / n).max((i + 1) as f64 / n - expected)
+        })
+        .fold(0.0, f64::max);
+    let critical = 1.949 / n.sqrt();
+    assert!(
+        statistic < critical,
+        "{name}: KS statistic {statistic} >= {critical}"
+    );
+}
+
+/// Runs a chi-square goodness-of-fit test of integer samples against a PMF, at a significance
+/// level of about 0.001, merging neighbouring values so every bin expects at least 5 samples.
+fn assert_matches_pmf(samples: &[u64], pmf: impl Fn(u64) -> f64, name: &str) {
+    let n = samples.len() as f64;
+    let mut observed_counts = std::collections::BTreeMap::new();
+    for &x in samples {
+        *observed_counts.entry(x).or_insert(0u64) += 1;
+    }
+    let (mut statistic, mut bins) = (0.0, 0);
+    let (mut expected, mut observed, mut remaining) = (0.0, 0.0, 1.0);
+    let mut k = 0;
+    // Close a bin once it expects 5 samples, as long as the tail left over expects 5 too
+    while remaining * n >= 10.0 {
+        let probability = pmf(k);
+        expected += probability * n;
+        observed += observed_counts.get(&k).copied().unwrap_or(0) as f64;
+        remaining -= probability;
+        k += 1;
+        if expected >= 5.0 && remaining * n >= 5.0 {
+            statistic += (observed - expected).powi(2) / expected;
+            bins += 1;
+            (expected, observed) = (0.0, 0.0);
+        }
+    }
+    // Whatever is left, including the tail beyond `k`, forms the last bin
+    expected += remaining.max(0.0) * n;
+    observed += observed_counts
+        .range(k..)
+        .map(|(_, &count)| count)
+        .sum::<u64>() as f64;
+    statistic += (observed - expected).powi(2) / expected;
+    bins += 1;
+    assert!(bins >= 2, "{name}: too few bins");
+    // The Wilson–Hilferty approximation of the chi-square quantile, at z = 3.09
+    let degrees_of_freedom = (bins - 1) as f64;
+    let spread = 2.0 / (9.0 * degrees_of_freedom);
+    let critical = degrees_of_freedom * (1.0 - spread + 3.09 * spread.sqrt()).powi(3);
+    assert!(
+        statistic < critical,
+        "{name}: chi-square statistic {statistic} >= {critical} with {bins} bins"
+    );
+}
+
+fn ln_gamma(x: f64) -> f64 {
+    // Lanczos approximation, with g = 7 and 9 coefficients
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut sum = COEFFICIENTS[0];
+    for (i, &coefficient) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += coefficient / (x + i as f64);
+    }
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The regularized lower incomplete gamma function P(a, x).
+fn gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let prefactor = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum, mut denominator) = (1.0 / a, 1.0 / a, a);
+        while term.abs() > sum.abs() * 1e-16 {
+            denominator += 1.0;
+            term *= x / denominator;
+            sum += term;
+        }
+        sum * prefactor
+    } else {
+        1.0 - prefactor
+            * continued_fraction(
+                |i| (-(i as f64) * (i as f64 - a), x + 2.0 * i as f64 + 1.0 - a),
+                x + 1.0 - a,
+            )
+    }
+}
+
+/// The regularized incomplete beta function I_x(a, b).
+fn beta_i(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    if x > (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - beta_i(b, a, 1.0 - x);
+    }
+    let ln_prefactor =
+        ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    // The continued fraction starts from 1 / (1 - (a + b) x / (a + 1)), then alternates
+    // between even and odd terms
+    let terms = |i: usize| {
+        let m = i.div_ceil(2) as f64;
+        let numerator = if i.is_multiple_of(2) {
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m))
+        } else {
+            -(a + m - 1.0) * (a + b + m - 1.0) * x / ((a + 2.0 * m - 2.0) * (a + 2.0 * m - 1.0))
+        };
+        (numerator, 1.0)
+    };
+    ln_prefactor.exp() / a * continued_fraction(terms, 1.0)
+}
+
+/// Evaluates `1 / (b0 + a1 / (b1 + a2 / (b2 + ...)))` with the modified Lentz method.
+fn continued_fraction(terms: impl Fn(usize) -> (f64, f64), b0: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b0;
+    let mut h = d;
+    for i in 1..10_000 {
+        let (a, b) = terms(i);
+        d = b + a * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + a / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = c * d;
+        h *= delta;
+        if (delta - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    h
+}
+
+fn samples(seed: u64, fill: impl FnOnce(&mut XorshiftrWide, &mut [f64])) -> Vec<f64> {
+    let mut values = vec![0.0; SAMPLES];
+    fill(&mut XorshiftrWide::from_seed_u64(seed), &mut values);
+    values
+}
+
+fn counts(seed: u64, fill: impl FnOnce(&mut XorshiftrWide, &mut [u64])) -> Vec<u64> {
+    let mut values = vec![0; SAMPLES];
+    fill(&mut XorshiftrWide::from_seed_u64(seed), &mut values);
+    values
+}
+
+fn ln_choose(n: u64, k: u64) -> f64 {
+    // A sum of logs, rather than differences of ln_gamma, stays accurate for huge `n`
+    let k = k.min(n - k);
+    (1..=k).map(|i| ((n - k + i) as f64 / i as f64).ln()).sum()
+}
+
+#[test]
+fn special_functions_match_known_values() {
+    assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+    assert!((ln_gamma(10.0) - 362_880f64.ln()).abs() < 1e-12);
+    assert!((gamma_p(1.0, 2.0) - (1.0 - (-2.0f64).exp())).abs() < 1e-12);
+    assert!((gamma_p(3.0, 5.0) - 0.875_347_980_516_919_5).abs() < 1e-12);
+    assert!((beta_i(2.0, 3.0, 0.4) - 0.5248).abs() < 1e-12);
+    assert!((beta_i(0.5, 0.5, 0.25) - 1.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn exponential_matches_cdf() {
+    for lambda in [0.5, 1.0, 40.0] {
+        let exponential = Exponential::new(lambda).unwrap();
+        let values = samples(1, |rng, values| exponential.fill(rng, values));
+        assert_matches_cdf(values, |x| 1.0 - (-lambda * x).exp(), "exponential");
+    }
+}
+
+#[test]
+fn gamma_matches_cdf() {
+    for (shape, scale) in [(0.3, 2.0), (1.0, 1.0), (5.5, 0.5), (100.0, 3.0)] {
+        let gamma = Gamma::new(shape, scale).unwrap();
+        let values = samples(2, |rng, values| gamma.fill(rng, values));
+        assert_matches_cdf(values, |x| gamma_p(shape, x / scale), "gamma");
+    }
+}
+
+#[test]
+fn beta_matches_cdf() {
+    for (alpha, beta) in [(0.5, 0.5), (2.0, 5.0), (0.2, 0.3), (30.0, 1.5)] {
+        let distribution = Beta::new(alpha, beta).unwrap();
+        let values = samples(3, |rng, values| distribution.fill(rng, values));
+        assert!(values.iter().all(|x| (0.0..=1.0).contains(x)));
+        assert_matches_cdf(
+            values,
+            |x| beta_i(alpha, beta, x),
+            &format!("beta({alpha}, {beta})"),
+        );
+    }
+}
+
+#[test]
+fn beta_with_tiny_shapes_stays_in_range() {
+    // Most of these samples round to exactly 0 or 1, which rules out comparing against the CDF,
+    // but the gamma samples they come from would underflow to zero if taken directly
+    let distribution = Beta::new(0.01, 0.02).unwrap();
+    let values = samples(4, |rng, values| distribution.fill(rng, values));
+    assert!(values.iter().all(|x| (0.0..=1.0).contains(x)));
+    let upper = values.iter().filter(|&&x| x > 0.5).count() as f64 / SAMPLES as f64;
+    // P(X > 0.5) is very nearly alpha / (alpha + beta) for tiny shapes
+    assert!((upper - 1.0 / 3.0).abs() < 0.01, "{upper}");
+}
+
+#[test]
+fn chi_squared_matches_cdf() {
+    for degrees_of_freedom in [1.0, 2.5, 10.0] {
+        let chi_squared = ChiSquared::new(degrees_of_freedom).unwrap();
+        let values = samples(4, |rng, values| chi_squared.fill(rng, values));
+        assert_matches_cdf(
+            values,
+            |x| gamma_p(degrees_of_freedom / 2.0, x / 2.0),
+            "chi-squared",
+        );
+    }
+}
+
+#[test]
+fn bernoulli_matches_pmf() {
+    for p in [0.001, 0.3, 0.5, 0.97] {
+        let bernoulli = Bernoulli::new(p).unwrap();
+        let mut values = vec![false; SAMPLES];
+        bernoulli.fill(&mut XorshiftrWide::<16>::from_seed_u64(5), &mut values);
+        let values: Vec<u64> = values.into_iter().map(u64::from).collect();
+        assert_matches_pmf(
+            &values,
+            |k| if k == 0 { 1.0 - p } else { p },
+            &format!("bernoulli({p})"),
+        );
+    }
+    let mut values = vec![false; 1000];
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(5);
+    Bernoulli::new(1.0).unwrap().fill(&mut rng, &mut values);
+    assert!(values.iter().all(|&x| x));
+    Bernoulli::new(0.0).unwrap().fill(&mut rng, &mut values);
+    assert!(values.iter().all(|&x| !x));
+}
+
+#[test]
+fn binomial_matches_pmf() {
+    // Both inversion and BTPE, each with `p` on either side of 0.5,
+    // and with `n` too large for `n + 1` or `1 - p` to be exact
+    for (n, p) in [
+        (20, 0.3),
+        (100, 0.9),
+        (1000, 0.05),
+        (60, 0.5),
+        (10_000, 0.3),
+        (5000, 0.99),
+        (1 << 62, 1e-18),
+        (1 << 62, 1000.0 / (1u64 << 62) as f64),
+    ] {
+        let binomial = Binomial::new(n, p).unwrap();
+        let values = counts(6, |rng, values| binomial.fill(rng, values));
+        assert!(values.iter().all(|&x| x <= n));
+        assert_matches_pmf(
+            &values,
+            |k| {
+                if k > n {
+                    return 0.0;
+                }
+                (ln_choose(n, k) + k as f64 * p.ln() + (n - k) as f64 * (-p).ln_1p()).exp()
+            },
+            &format!("binomial({n}, {p})"),
+        );
+    }
+    let mut values = vec![1; 100];
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(6);
+    Binomial::new(40, 0.0).unwrap().fill(&mut rng, &mut values);
+    assert!(values.iter().all(|&x| x == 0));
+    Binomial::new(40, 1.0).unwrap().fill(&mut rng, &mut values);
+    assert!(values.iter().all(|&x| x == 40));
+}
+
+#[test]
+fn poisson_matches_pmf() {
+    // Both the multiplication method and PTRS
+    for lambda in [0.1, 3.0, 9.9, 10.0, 45.5, 2000.0] {
+        let poisson = Poisson::new(lambda).unwrap();
+        let values = counts(7, |rng, values| poisson.fill(rng, values));
+        assert_matches_pmf(
+            &values,
+            |k| (k as f64 * lambda.ln() - lambda - ln_gamma(k as f64 + 1.0)).exp(),
+            &format!("poisson({lambda})"),
+        );
+    }
+}
+
+#[test]
+fn geometric_matches_pmf() {
+    for p in [0.001, 0.2, 0.5, 0.95] {
+        let geometric = Geometric::new(p).unwrap();
+        let values = counts(8, |rng, values| geometric.fill(rng, values));
+        assert_matches_pmf(
+            &values,
+            |k| p * (k as f64 * (-p).ln_1p()).exp(),
+            &format!("geometric({p})"),
+        );
+    }
+    let mut values = vec![1; 100];
+    Geometric::new(1.0)
+        .unwrap()
+        .fill(&mut XorshiftrWide::<16>::from_seed_u64(8), &mut values);
+    assert!(values.iter().all(|&x| x == 0));
+}
+
+#[test]
+fn alias_table_matches_weights() {
+    let mut weights: Vec<f64> = (0..1000).map(|i| ((i * 7919) % 1000) as f64).collect();
+    weights[3] = 2e5;
+    weights[500] = 1e-3;
+    let cases = [vec![0.0, 3.0, 0.0, 1.0], vec![f64::MAX; 3], weights];
+    for weights in cases {
+        let table = AliasTable::new(&weights).unwrap();
+        assert_eq!(table.len(), weights.len());
+        let mut indices = vec![0u32; SAMPLES];
+        table.fill(&mut XorshiftrWide::<16>::from_seed_u64(9), &mut indices);
+        assert!(indices.iter().all(|&index| weights[index as usize] > 0.0));
+        let indices: Vec<u64> = indices.into_iter().map(u64::from).collect();
+        let largest = weights.iter().copied().fold(0.0, f64::max);
+        let total: f64 = weights.iter().map(|weight| weight / largest).sum();
+        assert_matches_pmf(
+            &indices,
+            |k| {
+                weights
+                    .get(k as usize)
+                    .map_or(0.0, |weight| weight / largest / total)
+            },
+            &format!("alias table with {} weights", weights.len()),
+        );
+    }
+    let mut indices = vec![1u32; 100];
+    AliasTable::new(&[5.0])
+        .unwrap()
+        .fill(&mut XorshiftrWide::<16>::from_seed_u64(9), &mut indices);
+    assert!(indices.iter().all(|&index| index == 0));
+}
+
+#[test]
+fn invalid_weights_are_rejected() {
+    assert_eq!(AliasTable::new(&[]), Err(WeightError::Empty));
+    assert_eq!(AliasTable::new(&[0.0, 0.0]), Err(WeightError::AllZero));
+    assert_eq!(
+        AliasTable::new(&[1.0, -0.5]),
+        Err(WeightError::Negative { index: 1 })
+    );
+    assert_eq!(
+        AliasTable::new(&[1.0, 2.0, f64::NAN]),
+        Err(WeightError::NotFinite { index: 2 })
+    );
+    assert_eq!(
+        AliasTable::new(&[f64::INFINITY]),
+        Err(WeightError::NotFinite { index: 0 })
+    );
+}
+
+#[test]
+fn invalid_parameters_are_rejected() {
+    assert_eq!(
+        Exponential::new(0.0),
+        Err(ParameterError::NotPositive("lambda"))
+    );
+    assert_eq!(
+        Exponential::new(f64::NAN),
+        Err(ParameterError::NotFinite("lambda"))
+    );
+    assert_eq!(
+        Gamma::new(1.0, -1.0),
+        Err(ParameterError::NotPositive("scale"))
+    );
+    assert_eq!(
+        Gamma::new(f64::INFINITY, 1.0),
+        Err(ParameterError::NotFinite("shape"))
+    );
+    assert_eq!(
+        Beta::new(1.0, 0.0),
+        Err(ParameterError::NotPositive("beta"))
+    );
+    assert_eq!(
+        ChiSquared::new(-3.0),
+        Err(ParameterError::NotPositive("degrees_of_freedom"))
+    );
+    assert_eq!(
+        Bernoulli::new(1.5),
+        Err(ParameterError::NotProbability("p"))
+    );
+    assert_eq!(
+        Binomial::new(10, f64::NAN),
+        Err(ParameterError::NotProbability("p"))
+    );
+    assert_eq!(Geometric::new(0.0), Err(ParameterError::NotPositive("p")));
+    assert_eq!(
+        Poisson::new(f64::INFINITY),
+        Err(ParameterError::NotFinite("lambda"))
+    );
+}
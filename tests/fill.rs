@@ -0,0 +1,214 @@
+use xorshiftr_wide::XorshiftrWide;
+
+const LEN: usize = 10_000 + 5;
+
+fn words(seed: u64, len: usize) -> Vec<u64> {
+    let mut words = vec![0; len];
+    XorshiftrWide::<16>::from_seed_u64(seed).fill_u64_buffer(&mut words);
+    words
+}
+
+#[test]
+fn f64_fills_use_high_bits_of_each_word() {
+    let expected = words(1, LEN);
+    let mut values = vec![0.0; LEN];
+    XorshiftrWide::<16>::from_seed_u64(1).fill_f64(&mut values);
+    for (value, word) in values.iter().zip(&expected) {
+        assert_eq!(*value, (word >> 11) as f64 / (1u64 << 53) as f64);
+        assert!((0.0..1.0).contains(value));
+    }
+    XorshiftrWide::<16>::from_seed_u64(1).fill_f64_open_closed(&mut values);
+    assert!(values.iter().all(|&value| value > 0.0 && value <= 1.0));
+    XorshiftrWide::<16>::from_seed_u64(1).fill_f64_open(&mut values);
+    assert!(values.iter().all(|&value| value > 0.0 && value < 1.0));
+    let mean = values.iter().sum::<f64>() / LEN as f64;
+    assert!((mean - 0.5).abs() < 0.01);
+}
+
+#[test]
+fn f32_fills_take_two_values_per_word() {
+    let expected = words(2, LEN.div_ceil(2));
+    let mut values = vec![0.0f32; LEN];
+    XorshiftrWide::<16>::from_seed_u64(2).fill_f32(&mut values);
+    for (pair, word) in values.chunks(2).zip(&expected) {
+        assert_eq!(pair[0], ((*word as u32) >> 8) as f32 / (1u32 << 24) as f32);
+        if let Some(&high) = pair.get(1) {
+            assert_eq!(high, ((word >> 40) as u32) as f32 / (1u32 << 24) as f32);
+        }
+    }
+    assert!(values.iter().all(|value| (0.0..1.0).contains(value)));
+    XorshiftrWide::<16>::from_seed_u64(2).fill_f32_open_closed(&mut values);
+    assert!(values.iter().all(|&value| value > 0.0 && value <= 1.0));
+    XorshiftrWide::<16>::from_seed_u64(2).fill_f32_open(&mut values);
+    assert!(values.iter().all(|&value| value > 0.0 && value < 1.0));
+}
+
+#[test]
+fn bounded_fills_stay_in_range() {
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(3);
+    let mut values = vec![0u64; LEN];
+    rng.fill_range_u64(&mut values, 10..17);
+    assert!(values.iter().all(|value| (10..17).contains(value)));
+    // About half of all words are rejected with this span, exercising the redraw pass
+    let huge = (1 << 63) + 1;
+    rng.fill_range_u64(&mut values, 5..5 + huge);
+    assert!(values.iter().all(|value| (5..5 + huge).contains(value)));
+    assert!(values.iter().any(|&value| value >= 5 + (1 << 62)));
+    rng.fill_range_inclusive_u64(&mut values, 3..=3);
+    assert!(values.iter().all(|&value| value == 3));
+
+    let mut values = vec![0i64; LEN];
+    rng.fill_range_i64(&mut values, -3..2);
+    assert!(values.iter().all(|value| (-3..2).contains(value)));
+    rng.fill_range_inclusive_i64(&mut values, i64::MIN..=i64::MIN + 1);
+    assert!(values.iter().all(|&value| value <= i64::MIN + 1));
+
+    let mut values = vec![0u32; LEN];
+    rng.fill_range_u32(&mut values, 0..(1 << 31) + 1);
+    assert!(values.iter().all(|&value| value <= 1 << 31));
+    rng.fill_range_inclusive_u32(&mut values, 7..=9);
+    assert!(values.iter().all(|value| (7..=9).contains(value)));
+
+    let mut values = vec![0i32; LEN];
+    rng.fill_range_i32(&mut values, -100..-90);
+    assert!(values.iter().all(|value| (-100..-90).contains(value)));
+    rng.fill_range_inclusive_i32(&mut values, -1..=1);
+    assert!(values.iter().all(|value| (-1..=1).contains(value)));
+}
+
+#[test]
+fn full_inclusive_ranges_pass_output_through() {
+    let expected = words(4, LEN);
+    let mut values = vec![0u64; LEN];
+    XorshiftrWide::<16>::from_seed_u64(4).fill_range_inclusive_u64(&mut values, 0..=u64::MAX);
+    assert_eq!(values, expected);
+    let mut values = vec![0i64; LEN];
+    XorshiftrWide::<16>::from_seed_u64(4)
+        .fill_range_inclusive_i64(&mut values, i64::MIN..=i64::MAX);
+    assert!(
+        values
+            .iter()
+            .zip(&expected)
+            .all(|(&value, &word)| value == (word as i64) ^ i64::MIN)
+    );
+}
+
+#[test]
+fn bounded_fills_are_unbiased() {
+    // With a span of 3 * 2^62, multiplying without rejection would make multiples of 3
+    // twice as likely as other values, so they would make up half the output instead of a third.
+    let span = 3 << 62;
+    let mut values = vec![0u64; 30_000];
+    XorshiftrWide::<16>::from_seed_u64(5).fill_range_u64(&mut values, 0..span);
+    let multiples_of_three = values.iter().filter(|&&value| value % 3 == 0).count();
+    assert!(
+        multiples_of_three.abs_diff(10_000) < 500,
+        "{multiples_of_three}"
+    );
+}
+
+#[test]
+fn integer_fills_match_little_endian_bytes() {
+    let rng = XorshiftrWide::<16>::from_seed_u64(6);
+    let mut bytes = vec![0u8; LEN * 16];
+    rng.clone().fill_bytes(&mut bytes);
+
+    let mut values = vec![0u16; LEN];
+    rng.clone().fill_u16(&mut values);
+    let expected: Vec<u16> = bytes
+        .chunks(2)
+        .map(|chunk| u16::from_le_bytes(chunk.try_into().unwrap()))
+        .take(LEN)
+        .collect();
+    assert_eq!(values, expected);
+
+    let mut values = vec![0u32; LEN];
+    rng.clone().fill_u32(&mut values);
+    let expected: Vec<u32> = bytes
+        .chunks(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .take(LEN)
+        .collect();
+    assert_eq!(values, expected);
+
+    let mut values = vec![0u128; LEN];
+    rng.clone().fill_u128(&mut values);
+    let expected: Vec<u128> = bytes
+        .chunks(16)
+        .map(|chunk| u128::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    assert_eq!(values, expected);
+
+    let mut values = vec![0i8; LEN];
+    rng.clone().fill_i8(&mut values);
+    assert!(
+        values
+            .iter()
+            .zip(&bytes)
+            .all(|(&value, &byte)| value as u8 == byte)
+    );
+    let mut values = vec![0i64; LEN];
+    rng.clone().fill_i64(&mut values);
+    let expected: Vec<i64> = bytes
+        .chunks(8)
+        .map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()))
+        .take(LEN)
+        .collect();
+    assert_eq!(values, expected);
+    let mut values = vec![0i128; LEN];
+    rng.clone().fill_i128(&mut values);
+    assert_eq!(
+        values[LEN - 1],
+        i128::from_le_bytes(bytes[(LEN - 1) * 16..].try_into().unwrap())
+    );
+}
+
+#[test]
+fn bernoulli_bits_have_the_requested_density() {
+    let mut values = vec![0u64; LEN];
+    XorshiftrWide::<16>::from_seed_u64(9).fill_bernoulli_bits(&mut values, 0.5);
+    assert_eq!(values, words(9, LEN));
+    // 0.25 is 0.01 in binary, so each word is the AND of two words of output
+    XorshiftrWide::<16>::from_seed_u64(9).fill_bernoulli_bits(&mut values[..96], 0.25);
+    let expected = words(9, 192);
+    let (first, second) = expected.split_at(96);
+    for ((value, first), second) in values.iter().zip(first).zip(second) {
+        assert_eq!(*value, first & second);
+    }
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(9);
+    for p in [0.001, 0.1, 0.3, 0.75, 0.999] {
+        rng.fill_bernoulli_bits(&mut values, p);
+        let density =
+            values.iter().map(|value| value.count_ones()).sum::<u32>() as f64 / (LEN * 64) as f64;
+        // Six standard deviations of the sample density
+        let tolerance = 6.0 * (p * (1.0 - p) / (LEN * 64) as f64).sqrt();
+        assert!(
+            (density - p).abs() < tolerance,
+            "p = {p}: density {density}"
+        );
+    }
+    // 2^-40 needs more than 32 bits of precision, and takes the AND of 40 words
+    let mut values = [0u64; 16];
+    XorshiftrWide::<16>::from_seed_u64(9).fill_bernoulli_bits(&mut values, 2f64.powi(-40));
+    let expected = words(9, 16 * 40);
+    for (i, value) in values.iter().enumerate() {
+        let and = expected
+            .iter()
+            .skip(i)
+            .step_by(16)
+            .fold(u64::MAX, |and, word| and & word);
+        assert_eq!(*value, and);
+    }
+    // A `p` very close to 0 or 1 gets enough precision to stay apart from it
+    let mut values = vec![0u64; LEN];
+    rng.fill_bernoulli_bits(&mut values, 1e-12);
+    assert!(values.iter().all(|&value| value == 0));
+    rng.fill_bernoulli_bits(&mut values, 1.0 - 1e-12);
+    assert!(values.iter().all(|&value| value == u64::MAX));
+    rng.fill_bernoulli_bits(&mut values, 1e-30);
+    assert!(values.iter().all(|&value| value == 0));
+    rng.fill_bernoulli_bits(&mut values, 0.0);
+    assert!(values.iter().all(|&value| value == 0));
+    rng.fill_bernoulli_bits(&mut values, 1.0);
+    assert!(values.iter().all(|&value| value == u64::MAX));
+}
@@ -0,0 +1,58 @@
+use std::io::{BufRead, Read};
+use xorshiftr_wide::{RandomReader, XorshiftrWide, write_random};
+
+fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    XorshiftrWide::<16>::from_seed_u64(seed).fill_bytes(&mut bytes);
+    bytes
+}
+
+#[test]
+fn reader_matches_fill_bytes() {
+    const LEN: usize = 50_003;
+    let expected = bytes(1, LEN);
+    let mut reader = RandomReader::new(XorshiftrWide::<16>::from_seed_u64(1));
+    let mut read = vec![0; LEN];
+    let mut position = 0;
+    // Small reads come from the internal buffer, and large ones go straight into `read`
+    for size in [1, 7, 200, 3000, 5, 128, 1024, 9000].into_iter().cycle() {
+        let end = LEN.min(position + size);
+        let len = reader.read(&mut read[position..end]).unwrap();
+        assert_ne!(len, 0);
+        position += len;
+        if position == LEN {
+            break;
+        }
+    }
+    assert_eq!(read, expected);
+}
+
+#[test]
+fn limited_reader_stops_after_the_limit() {
+    let expected = bytes(2, 10_001);
+    let mut reader = RandomReader::with_limit(XorshiftrWide::<16>::from_seed_u64(2), 10_001);
+    let first_line = reader.fill_buf().unwrap().to_vec();
+    assert_eq!(first_line, expected[..first_line.len()]);
+    reader.consume(3);
+    assert_eq!(reader.remaining(), Some(9998));
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, expected[3..]);
+    assert_eq!(reader.remaining(), Some(0));
+    assert_eq!(reader.read(&mut [0; 16]).unwrap(), 0);
+    assert!(reader.fill_buf().unwrap().is_empty());
+}
+
+#[test]
+fn write_random_matches_fill_bytes() {
+    for len in [0, 5, 1024, 200_003] {
+        let mut rng = XorshiftrWide::<16>::from_seed_u64(3);
+        let mut written = Vec::new();
+        write_random(&mut rng, &mut written, len as u64).unwrap();
+        assert_eq!(written, bytes(3, len));
+        // The prng ends up where filling the bytes would have left it
+        let mut expected_rng = XorshiftrWide::<16>::from_seed_u64(3);
+        expected_rng.fill_bytes(&mut vec![0; len]);
+        assert_eq!(rng.state(), expected_rng.state());
+    }
+}
@@ -0,0 +1,23 @@
+use xorshiftr_wide::XorshiftrWide;
+
+#[test]
+fn jump_by_skips_whole_blocks() {
+    let mut expected = vec![0u64; 100 * 16];
+    XorshiftrWide::<16>::from_seed_u64(1).fill_u64_buffer(&mut expected);
+    for steps in [0, 1, 7, 50] {
+        let mut rng = XorshiftrWide::<16>::from_seed_u64(1);
+        rng.jump_by(steps);
+        let mut jumped = vec![0u64; 40 * 16];
+        rng.fill_u64_buffer(&mut jumped);
+        let skipped = steps as usize * 16;
+        assert_eq!(jumped, expected[skipped..][..jumped.len()], "{steps} steps");
+    }
+    // Lane counts the SIMD kernels don't handle step the same way
+    let mut expected = vec![0u64; 30 * 5];
+    XorshiftrWide::<5>::from_seed_u64(2).fill_u64_buffer(&mut expected);
+    let mut rng = XorshiftrWide::<5>::from_seed_u64(2);
+    rng.jump_by(11);
+    let mut jumped = vec![0u64; 19 * 5];
+    rng.fill_u64_buffer(&mut jumped);
+    assert_eq!(jumped, expected[11 * 5..]);
+}
@@ -0,0 +1,57 @@
+use xorshiftr_wide::{Kernel, XorshiftrWideWith};
+
+const KERNELS: [Kernel; 3] = [Kernel::Portable, Kernel::Avx2, Kernel::Avx512];
+
+fn kernels_match_portable<
+    const LANES: usize,
+    const SHL_FIRST: bool,
+    const SHL: u32,
+    const SHR: u32,
+>() {
+    let rng = XorshiftrWideWith::<LANES, SHL_FIRST, SHL, SHR>::from_seed_u64(LANES as u64);
+    // Lengths around the kernels' internal tile size, plus a short tail
+    for blocks in [0, 1, 31, 32, 33, 100] {
+        let len = blocks * LANES + LANES / 2;
+        let mut portable = rng;
+        let mut expected = vec![0; len];
+        portable.fill_u64_buffer_with_kernel(Kernel::Portable, &mut expected);
+        for kernel in KERNELS.into_iter().filter(|kernel| kernel.is_supported()) {
+            let mut accelerated = rng;
+            let mut actual = vec![0; len];
+            accelerated.fill_u64_buffer_with_kernel(kernel, &mut actual);
+            assert_eq!(
+                actual, expected,
+                "{kernel:?} with {LANES} lanes, {len} words"
+            );
+            assert_eq!(accelerated.state(), portable.state());
+        }
+    }
+}
+
+#[test]
+fn kernels_match_portable_with_default_shifts() {
+    kernels_match_portable::<4, false, 17, 23>();
+    kernels_match_portable::<8, false, 17, 23>();
+    kernels_match_portable::<12, false, 17, 23>();
+    kernels_match_portable::<16, false, 17, 23>();
+    kernels_match_portable::<64, false, 17, 23>();
+}
+
+#[test]
+fn kernels_match_portable_with_other_shifts() {
+    kernels_match_portable::<16, true, 23, 17>();
+    kernels_match_portable::<8, true, 1, 63>();
+}
+
+#[test]
+fn kernels_fall_back_for_unsupported_lane_counts() {
+    kernels_match_portable::<1, false, 17, 23>();
+    kernels_match_portable::<3, false, 17, 23>();
+    kernels_match_portable::<6, false, 17, 23>();
+}
+
+#[test]
+fn detected_kernel_is_supported() {
+    assert!(Kernel::detect().is_supported());
+    assert!(Kernel::Portable.is_supported());
+}
@@ -0,0 +1,90 @@
+use xorshiftr_wide::XorshiftrWide;
+
+const SAMPLES: usize = 1_000_001;
+
+struct Moments {
+    mean: f64,
+    variance: f64,
+    skewness: f64,
+    kurtosis: f64,
+}
+
+fn moments_of(values: impl Iterator<Item = f64> + Clone) -> Moments {
+    let n = values.clone().count() as f64;
+    let mean = values.clone().sum::<f64>() / n;
+    let central = |power: i32| values.clone().map(|x| (x - mean).powi(power)).sum::<f64>() / n;
+    let variance = central(2);
+    Moments {
+        mean,
+        variance,
+        skewness: central(3) / variance.powf(1.5),
+        kurtosis: central(4) / variance.powi(2),
+    }
+}
+
+/// Checks the fraction of standardized values beyond 1, 2, 3 and 4 standard deviations
+/// against the normal distribution, allowing 5 binomial standard errors.
+fn check_tails(standardized: impl Iterator<Item = f64> + Clone) {
+    let n = standardized.clone().count() as f64;
+    let expected = [
+        (1.0, 0.317_310_507_862_914_2),
+        (2.0, 0.045_500_263_896_358_4),
+        (3.0, 0.002_699_796_063_260_2),
+        (4.0, 0.000_063_342_483_666_3),
+    ];
+    for (threshold, probability) in expected {
+        let count = standardized.clone().filter(|x| x.abs() > threshold).count() as f64;
+        let tolerance = 5.0 * (n * probability * (1.0 - probability)).sqrt();
+        assert!(
+            (count - n * probability).abs() < tolerance,
+            "{count} values beyond {threshold} standard deviations, expected {}",
+            n * probability
+        );
+    }
+}
+
+#[test]
+fn normal_f64_has_standard_moments_and_tails() {
+    let mut values = vec![0.0; SAMPLES];
+    XorshiftrWide::<16>::from_seed_u64(1).fill_normal_f64(&mut values, 0.0, 1.0);
+    let moments = moments_of(values.iter().copied());
+    assert!(moments.mean.abs() < 0.005);
+    assert!((moments.variance - 1.0).abs() < 0.01);
+    assert!(moments.skewness.abs() < 0.01);
+    assert!((moments.kurtosis - 3.0).abs() < 0.03);
+    check_tails(values.iter().copied());
+    assert!(values.iter().all(|x| x.abs() < 8.6));
+}
+
+#[test]
+fn normal_f32_has_standard_moments_and_tails() {
+    let mut values = vec![0.0f32; SAMPLES];
+    XorshiftrWide::<16>::from_seed_u64(2).fill_normal_f32(&mut values, 0.0, 1.0);
+    let moments = moments_of(values.iter().map(|&x| x as f64));
+    assert!(moments.mean.abs() < 0.005);
+    assert!((moments.variance - 1.0).abs() < 0.01);
+    assert!(moments.skewness.abs() < 0.01);
+    assert!((moments.kurtosis - 3.0).abs() < 0.03);
+    check_tails(values.iter().map(|&x| x as f64));
+    assert!(values.iter().all(|x| x.abs() < 5.8));
+}
+
+#[test]
+fn normal_fills_apply_mean_and_std_dev() {
+    let mut values = vec![0.0; SAMPLES];
+    XorshiftrWide::<16>::from_seed_u64(3).fill_normal_f64(&mut values, 10.0, 3.0);
+    let moments = moments_of(values.iter().copied());
+    assert!((moments.mean - 10.0).abs() < 0.015);
+    assert!((moments.variance - 9.0).abs() < 0.09);
+    check_tails(values.iter().map(|x| (x - 10.0) / 3.0));
+
+    let mut values = vec![0.0f32; SAMPLES];
+    XorshiftrWide::<16>::from_seed_u64(4).fill_normal_f32(&mut values, -2.0, 0.5);
+    let moments = moments_of(values.iter().map(|&x| x as f64));
+    assert!((moments.mean + 2.0).abs() < 0.0025);
+    assert!((moments.variance - 0.25).abs() < 0.0025);
+
+    let mut values = [1.0; 3];
+    XorshiftrWide::<16>::from_seed_u64(5).fill_normal_f64(&mut values, 4.0, 0.0);
+    assert_eq!(values, [4.0; 3]);
+}
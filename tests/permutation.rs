@@ -0,0 +1,37 @@
+use xorshiftr_wide::{RandomPermutation, XorshiftrWide};
+
+#[test]
+fn permutations_are_bijections() {
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(1);
+    for len in (0..=300).chain([1000, 1024, 1025, 65_537]) {
+        let permutation = RandomPermutation::new(&mut rng, len);
+        assert_eq!(permutation.len(), len);
+        let mut seen = vec![false; len as usize];
+        for value in permutation.iter() {
+            assert!(
+                !seen[value as usize],
+                "{value} appears twice for length {len}"
+            );
+            seen[value as usize] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+    }
+}
+
+#[test]
+fn permutations_are_random() {
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(2);
+    let first = RandomPermutation::new(&mut rng, 1000);
+    let second = RandomPermutation::new(&mut rng, 1000);
+    let fixed_points = first
+        .iter()
+        .enumerate()
+        .filter(|&(i, value)| i as u64 == value)
+        .count();
+    assert!(fixed_points < 10, "{fixed_points}");
+    assert!(first.iter().ne(second.iter()));
+    // Random access at the top of the range works, and gives the same as iterating
+    let huge = RandomPermutation::new(&mut rng, u64::MAX);
+    assert!(huge.get(u64::MAX - 1) < u64::MAX);
+    assert_eq!(first.get(999), first.iter().last().unwrap());
+}
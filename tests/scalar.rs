@@ -0,0 +1,49 @@
+use xorshiftr_wide::{XorshiftrScalar, XorshiftrWideWith};
+
+const STEPS: usize = 1000;
+
+fn lanes_match_scalar<const LANES: usize, const SHL_FIRST: bool, const SHL: u32, const SHR: u32>(
+    seed: u64,
+) {
+    let wide = XorshiftrWideWith::<LANES, SHL_FIRST, SHL, SHR>::from_seed_u64(seed);
+    let mut output = vec![0; LANES * STEPS];
+    let mut advanced = wide;
+    advanced.fill_u64_buffer(&mut output);
+    for lane in 0..LANES {
+        let mut scalar = XorshiftrScalar::<SHL_FIRST, SHL, SHR>::from_lane(&wide, lane);
+        for step in 0..STEPS {
+            assert_eq!(
+                output[step * LANES + lane],
+                scalar.next_u64(),
+                "lane {lane} of {LANES} diverged at step {step}"
+            );
+        }
+    }
+}
+
+#[test]
+fn lanes_match_scalar_with_default_shifts() {
+    lanes_match_scalar::<1, false, 17, 23>(1);
+    lanes_match_scalar::<3, false, 17, 23>(2);
+    lanes_match_scalar::<16, false, 17, 23>(3);
+    lanes_match_scalar::<64, false, 17, 23>(4);
+}
+
+#[test]
+fn lanes_match_scalar_with_other_shifts() {
+    lanes_match_scalar::<16, true, 23, 17>(5);
+    lanes_match_scalar::<8, true, 1, 63>(6);
+    lanes_match_scalar::<8, false, 63, 1>(7);
+}
+
+#[test]
+fn scalar_state_tracks_wide_state() {
+    let mut wide = XorshiftrWideWith::<4, false, 17, 23>::from_seed_u64(8);
+    let mut scalar = XorshiftrScalar::<false, 17, 23>::from_lane(&wide, 2);
+    wide.fill_u64_buffer(&mut [0; 4 * 10]);
+    for _ in 0..10 {
+        scalar.next_u64();
+    }
+    let state = wide.state();
+    assert_eq!(scalar.state(), [state[0][2], state[1][2]]);
+}
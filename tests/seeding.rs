@@ -0,0 +1,220 @@
+use xorshiftr_wide::XorshiftrWide;
+
+fn first_words<const N: usize>(mut rng: XorshiftrWide) -> [u64; N] {
+    let mut words = [0; N];
+    rng.fill_u64_buffer(&mut words);
+    words
+}
+
+#[test]
+fn from_seed_u64_known_answers() {
+    assert_eq!(
+        first_words(XorshiftrWide::from_seed_u64(0)),
+        [
+            0xb1dadd6644ffbf59,
+            0x53143fbd31ebb0b7,
+            0xbcee4c73e02d744f,
+            0x8a397322c72dd108,
+            0x0981cc2962caa038,
+            0x6c9c592a9744ba02,
+            0x194cae0787ce4edf,
+            0xe0e790d07c6cdeae,
+            0xcf3e6e9cdfb8aa40,
+            0x616ec550178ba8cb,
+            0xbd516ec6cf888770,
+            0x8c1861499eee1efc,
+            0xc446e6c4ea8fa4ba,
+            0x668c39e9a2ee021f,
+            0xa9b737d85ee26118,
+            0xf986fc5bbe68af85,
+            0x7865ce0b7328aabf,
+            0xeee07c350af15fd0,
+            0xe7f6754c52840e63,
+            0xc12c5226c96ed318,
+        ]
+    );
+    assert_eq!(
+        first_words(XorshiftrWide::from_seed_u64(0x0123_4567_89ab_cdef)),
+        [
+            0x652099d21b57a5d4,
+            0x7310f7381ff0e6fa,
+            0x4173c7769b1b918c,
+            0x9239d09ee69c0174,
+            0x989764ff30485221,
+            0xba76f26efac9d426,
+            0x0c3f6becc14cacc5,
+            0x23ab0f211caa18ce,
+            0x4cff2b4f7f0b43f9,
+            0x86d1458e84312771,
+            0x5d151fb46987413b,
+            0xaa3d78b922d2574d,
+            0x87e57557dc44125c,
+            0x18993f32fe728118,
+            0x2e5661a17778811f,
+            0x4df758af4b4e80cf,
+            0xa3d50c33d8a49e73,
+            0x75553e8482592022,
+            0xf8eb8596813c6dc9,
+            0xe2b3c06bf71a6d82,
+        ]
+    );
+    let mut four_lanes = XorshiftrWide::<4>::from_seed_u64(42);
+    let mut words = [0; 6];
+    four_lanes.fill_u64_buffer(&mut words);
+    assert_eq!(
+        words,
+        [
+            0xdb6c3043c41b22ca,
+            0xee2b3823416e9667,
+            0x88e1085bd5293174,
+            0x9a828e5d0c991d88,
+            0xb92ee1b8738c97ba,
+            0xbec95980ea1d2f7f,
+        ]
+    );
+}
+
+#[test]
+fn from_seed_u128_known_answers() {
+    assert_eq!(
+        first_words(XorshiftrWide::from_seed_u128(0)),
+        [
+            0x55cb1d8ffd32d331,
+            0x025b56d806af59b2,
+            0xde4e15860da53a75,
+            0x8408392c9c7f9178,
+            0xbcd49f56f5fdb1cb,
+            0xfe801e74e06f00a9,
+            0x8ba85f5a93cb0862,
+            0x49b1095edb0d3045,
+            0xc2be7b8ee4845bde,
+            0x9d28ce0b64fc1511,
+            0x268f8c9b664e1fc1,
+            0x5cb16ad574c66930,
+            0xaee5453812bc98dc,
+            0x89f320e24de23127,
+            0x99fb259d57ebd574,
+            0xb0ed1ff9c5e30d0d,
+            0x6a046c6a75adb911,
+            0xe8e68bab8bb2dcae,
+            0x1095f9eb9ccfe38d,
+            0x4d06d69be2598647,
+        ]
+    );
+    assert_eq!(
+        first_words(XorshiftrWide::from_seed_u128(
+            0x0123_4567_89ab_cdef_fedc_ba98_7654_3210
+        )),
+        [
+            0xff8ec406901b96fa,
+            0x310e2b09ad9e8c00,
+            0x152dee45d6a94a64,
+            0xda8ddf8fd4833a3f,
+            0x100df4740366dfe1,
+            0x74987df7f78b3ee7,
+            0x363dc87ad851978b,
+            0x6926f8dea9255f36,
+            0x48a0d8f14b8379ae,
+            0x7492b608f1803171,
+            0xf65e3050c17179f1,
+            0xf66ddb980cdca5db,
+            0xbf4f04bb27d6664c,
+            0xcf5d26facfac45b0,
+            0x0f9991a03fe5bd10,
+            0xa301faee01e63fb3,
+            0x8b6617ecd1389a46,
+            0x46d40d08f1169a63,
+            0xa1a1dcebc42a8d12,
+            0x90b53145144557a9,
+        ]
+    );
+}
+
+#[test]
+fn from_seed_u64_matches_reference_splitmix64() {
+    // The first lane's first state word is SplitMix64's first output for seed 0,
+    // a widely published value, and its first output is that word after the two shifts.
+    let mut x: u64 = 0xe220a8397b1dcdaf;
+    x ^= x >> 23;
+    x ^= x << 17;
+    assert_eq!(first_words::<1>(XorshiftrWide::from_seed_u64(0))[0], x);
+}
+
+#[test]
+fn different_seeds_give_different_streams() {
+    let words: [u64; 64] = first_words(XorshiftrWide::from_seed_u128(1));
+    let other: [u64; 64] = first_words(XorshiftrWide::from_seed_u128(1 << 64));
+    assert_ne!(words, other);
+    assert_ne!(words, first_words(XorshiftrWide::from_seed_u64(1)));
+}
+
+#[test]
+fn try_new_rejects_broken_sources() {
+    use xorshiftr_wide::StateError;
+    assert_eq!(
+        XorshiftrWide::<16>::try_new(|| 0).unwrap_err(),
+        StateError::ZeroLane { lane: 0 }
+    );
+    assert_eq!(
+        XorshiftrWide::<16>::try_new(|| 5).unwrap_err(),
+        StateError::EqualWordsInLane { lane: 0 }
+    );
+    // A counter with period 10 repeats itself within each row of the state
+    let mut counter = 0u64;
+    let short_period = || {
+        counter = (counter + 1) % 10;
+        counter
+    };
+    assert_eq!(
+        XorshiftrWide::<16>::try_new(short_period).unwrap_err(),
+        StateError::DuplicateLanes {
+            first: 0,
+            second: 10
+        }
+    );
+    let mut counter = 0u64;
+    assert!(
+        XorshiftrWide::<16>::try_new(|| {
+            counter += 1;
+            counter
+        })
+        .is_ok()
+    );
+}
+
+#[test]
+fn try_reseed_leaves_state_unchanged_on_failure() {
+    let mut rng = XorshiftrWide::from_seed_u64(7);
+    let expected: [u64; 32] = first_words(rng);
+    assert!(rng.try_reseed(|| 1).is_err());
+    assert_eq!(first_words::<32>(rng), expected);
+}
+
+#[test]
+fn state_round_trips() {
+    use xorshiftr_wide::StateError;
+    let mut rng = XorshiftrWide::<8>::from_seed_u64(3);
+    rng.fill_u64_buffer(&mut [0; 20]);
+    let mut restored = XorshiftrWide::try_from_state(rng.state()).unwrap();
+    let (mut expected, mut actual) = ([0; 40], [0; 40]);
+    rng.fill_u64_buffer(&mut expected);
+    restored.fill_u64_buffer(&mut actual);
+    assert_eq!(expected, actual);
+
+    let mut state = rng.state();
+    state[0][5] = 0;
+    state[1][5] = 0;
+    assert_eq!(
+        XorshiftrWide::try_from_state(state).unwrap_err(),
+        StateError::ZeroLane { lane: 5 }
+    );
+    let mut state = rng.state();
+    state[1][6] = state[1][2];
+    assert_eq!(
+        XorshiftrWide::try_from_state(state).unwrap_err(),
+        StateError::DuplicateLanes {
+            first: 2,
+            second: 6
+        }
+    );
+}
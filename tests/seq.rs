@@ -0,0 +1,161 @@
+use xorshiftr_wide::{Reservoir, XorshiftrWide};
+
+#[test]
+fn shuffle_is_pinned() {
+    // This must not change between versions of the crate
+    let mut values: Vec<u32> = (0..20).collect();
+    XorshiftrWide::<16>::from_seed_u64(1).shuffle(&mut values);
+    assert_eq!(
+        values,
+        [
+            15, 13, 0, 11, 17, 4, 9, 19, 1, 8, 18, 12, 7, 6, 10, 14, 3, 2, 16, 5
+        ]
+    );
+    // The first swap takes the high word of the first u64 of output times the length
+    let mut first_word = [0];
+    XorshiftrWide::<16>::from_seed_u64(1).fill_u64_buffer(&mut first_word);
+    assert_eq!(values[0] as u128, (first_word[0] as u128 * 20) >> 64);
+}
+
+/// Fisher-Yates driven straight from the u64 stream, with Lemire's method for each index.
+fn reference_shuffle(words: &[u64], len: u32) -> Vec<u32> {
+    let mut words = words.iter();
+    let mut values: Vec<u32> = (0..len).collect();
+    for position in 0..len.saturating_sub(1) {
+        let span = (len - position) as u64;
+        let threshold = span.wrapping_neg() % span;
+        let product = loop {
+            let product = *words.next().unwrap() as u128 * span as u128;
+            if product as u64 >= threshold {
+                break product;
+            }
+        };
+        values.swap(
+            position as usize,
+            position as usize + (product >> 64) as usize,
+        );
+    }
+    values
+}
+
+#[test]
+fn shuffle_takes_every_word_of_the_stream() {
+    // 1000 elements take several batches of words, which must not skip the end of a block
+    // when the lane count doesn't divide the batch size
+    let mut words = vec![0u64; 2000];
+    let mut values: Vec<u32> = (0..1000).collect();
+    XorshiftrWide::<12>::from_seed_u64(6).shuffle(&mut values);
+    XorshiftrWide::<12>::from_seed_u64(6).fill_u64_buffer(&mut words);
+    assert_eq!(values, reference_shuffle(&words, 1000));
+    let mut values: Vec<u32> = (0..1000).collect();
+    XorshiftrWide::<5>::from_seed_u64(6).shuffle(&mut values);
+    XorshiftrWide::<5>::from_seed_u64(6).fill_u64_buffer(&mut words);
+    assert_eq!(values, reference_shuffle(&words, 1000));
+    let mut values: Vec<u32> = (0..1000).collect();
+    XorshiftrWide::<16>::from_seed_u64(6).shuffle(&mut values);
+    XorshiftrWide::<16>::from_seed_u64(6).fill_u64_buffer(&mut words);
+    assert_eq!(values, reference_shuffle(&words, 1000));
+}
+
+#[test]
+fn partial_shuffle_matches_the_start_of_a_shuffle() {
+    let mut shuffled: Vec<u32> = (0..1000).collect();
+    XorshiftrWide::<16>::from_seed_u64(2).shuffle(&mut shuffled);
+    for amount in [0, 1, 10, 999, 1000, 5000] {
+        let mut values: Vec<u32> = (0..1000).collect();
+        let (chosen, rest) =
+            XorshiftrWide::<16>::from_seed_u64(2).partial_shuffle(&mut values, amount);
+        assert_eq!(chosen.len(), amount.min(1000));
+        assert_eq!(chosen, &shuffled[..chosen.len()]);
+        let mut all: Vec<u32> = chosen.iter().chain(rest.iter()).copied().collect();
+        all.sort();
+        assert!(all.iter().copied().eq(0..1000));
+    }
+    let mut empty: [u32; 0] = [];
+    XorshiftrWide::<16>::from_seed_u64(2).shuffle(&mut empty);
+}
+
+#[test]
+fn shuffle_is_uniform() {
+    // Each of the 24 orders of 4 elements should turn up equally often
+    const ROUNDS: usize = 240_000;
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(3);
+    let mut counts = std::collections::HashMap::new();
+    for _ in 0..ROUNDS {
+        let mut values = [0u8, 1, 2, 3];
+        rng.shuffle(&mut values);
+        *counts.entry(values).or_insert(0usize) += 1;
+    }
+    assert_eq!(counts.len(), 24);
+    let expected = (ROUNDS / 24) as f64;
+    let statistic: f64 = counts
+        .values()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum();
+    // The 0.999 quantile of the chi-square distribution with 23 degrees of freedom
+    assert!(statistic < 49.73, "{statistic}");
+}
+
+#[test]
+fn sample_indices_are_distinct_and_uniform() {
+    // Floyd's algorithm for the small samples, and a partial shuffle for the large ones
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(4);
+    for (length, amount) in [(10, 2), (10, 9), (50, 12), (50, 13), (7, 7), (5, 0)] {
+        const ROUNDS: usize = 20_000;
+        let mut inclusions = vec![0usize; length];
+        let mut firsts = vec![0usize; length];
+        for _ in 0..ROUNDS {
+            let indices = rng.sample_indices(length, amount);
+            assert_eq!(indices.len(), amount);
+            let mut sorted = indices.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(sorted.len(), amount);
+            for &index in &indices {
+                inclusions[index] += 1;
+            }
+            if let Some(&first) = indices.first() {
+                firsts[first] += 1;
+            }
+        }
+        // Every index should be included, and come first, equally often
+        let included = (ROUNDS * amount) as f64 / length as f64;
+        let first = if amount == 0 {
+            0.0
+        } else {
+            ROUNDS as f64 / length as f64
+        };
+        for index in 0..length {
+            assert!((inclusions[index] as f64 - included).abs() <= 6.0 * included.sqrt().max(1.0));
+            assert!((firsts[index] as f64 - first).abs() <= 6.0 * first.sqrt().max(1.0));
+        }
+    }
+    let large = rng.sample_indices(1 << 40, 1000);
+    assert!(large.iter().all(|&index| index < 1 << 40));
+}
+
+#[test]
+fn reservoir_keeps_a_uniform_sample() {
+    const ROUNDS: usize = 20_000;
+    let mut rng = XorshiftrWide::<16>::from_seed_u64(5);
+    let mut inclusions = [0usize; 100];
+    for _ in 0..ROUNDS {
+        let mut reservoir = Reservoir::new(10);
+        // Offering items across several calls gives the same distribution as one call
+        reservoir.extend(&mut rng, 0..30);
+        reservoir.extend(&mut rng, 30..100);
+        assert_eq!(reservoir.seen(), 100);
+        let sample = reservoir.into_sample();
+        assert_eq!(sample.len(), 10);
+        for item in sample {
+            inclusions[item] += 1;
+        }
+    }
+    let expected = ROUNDS as f64 / 10.0;
+    for count in inclusions {
+        assert!((count as f64 - expected).abs() <= 6.0 * expected.sqrt());
+    }
+    let mut reservoir = Reservoir::new(10);
+    reservoir.extend(&mut rng, "abc".chars());
+    assert_eq!(reservoir.sample(), ['a', 'b', 'c']);
+}